@@ -2,7 +2,7 @@ use crate::{
     components::{
         assignment::AssignmentCard, free::FreePlayCard, homework::HomeworkCard, user::UserCard,
     },
-    model::ui::Assignments,
+    model::ui::{Assignments, Presets},
 };
 
 use uuid::Uuid;
@@ -36,7 +36,8 @@ fn switch(routes: Route) -> Html {
 #[function_component]
 pub fn App() -> Html {
     let (_, d) = use_store::<Assignments>();
-    d.reduce_mut(|a| a.fill());
+    let (presets, _) = use_store::<Presets>();
+    d.reduce_mut(|a| a.fill(&presets));
     html! {
         <BrowserRouter>
         <div class="w3-content w3-margin-top" style="max-width: 1600px">
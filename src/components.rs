@@ -1,4 +1,6 @@
 pub mod assignment;
+pub mod designer;
 pub mod free;
 pub mod homework;
+pub mod soroban;
 pub mod user;
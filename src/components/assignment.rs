@@ -3,14 +3,17 @@ use std::rc::Rc;
 use chrono::Utc;
 use uuid::Uuid;
 use yew::prelude::*;
+use yew_hooks::use_interval;
 use yew_router::prelude::*;
 use yewdux::prelude::*;
 
 use crate::{
     app::Route,
+    components::soroban::Soroban,
     model::{
-        assignment::{Assignment, Progress},
-        task::Task,
+        assignment::{Assignment, Progress, SprintSummary},
+        criteria::{Criteria, Criterion, OnFailure},
+        task::{Answer, Operation, Prompt, Sampling, Task, TaskBuilderMode, Unknown},
         ui::Assignments,
     },
 };
@@ -41,23 +44,20 @@ pub fn AssignmentList(
         <ul class={classes!("w3-ul", "w3-margin-left", hoverable)}>
         {
             for assignments.iter().map(|id| {
-                let id = id.clone();
+                let id = *id;
                 let assignment = state.get(id);
                 let navigator = navigator.clone();
                 let onclick = if *active {
                     Callback::from(move |_| {
-                        navigator.push(&Route::Assignment { id: id });
+                        navigator.push(&Route::Assignment { id });
                     })
                 } else{
                     Callback::from(move |_| {})
                 };
-                let on_remove = {
-                    let id = id.clone();
-                    dispatch.reduce_mut_callback_with(move |assignments, e: MouseEvent|{
-                        assignments.remove(id);
-                        e.set_cancel_bubble(true);
-                    })
-                };
+                let on_remove = dispatch.reduce_mut_callback_with(move |assignments, e: MouseEvent|{
+                    assignments.remove(id);
+                    e.set_cancel_bubble(true);
+                });
                 html!{
                     if let Some(assignment) = assignment {
                         <li class="w3-bar" {onclick}>
@@ -84,6 +84,35 @@ pub fn AssignmentList(
     }
 }
 
+// The ways an assignment can pick its tasks, as offered in its header.
+const SAMPLINGS: [(Sampling, &str); 4] = [
+    (Sampling::Uniform, "Случайно"),
+    (Sampling::Adaptive, "Адаптивно"),
+    (Sampling::Deck { commutative: false }, "Без повторения"),
+    (
+        Sampling::Deck { commutative: true },
+        "Без повторения (a x b = b x a, a + b = b + a)",
+    ),
+];
+
+// The time limits per task an assignment can have, as offered in its header.
+pub(crate) const TASK_LIMITS: [(Option<u32>, &str); 5] = [
+    (None, "Без лимит"),
+    (Some(3), "3 сек. на пример"),
+    (Some(5), "5 сек. на пример"),
+    (Some(10), "10 сек. на пример"),
+    (Some(20), "20 сек. на пример"),
+];
+
+// The limits on skipped tasks an assignment can have, as offered in its header.
+const SKIP_LIMITS: [(Option<u32>, &str); 5] = [
+    (None, "Пропускане без лимит"),
+    (Some(0), "Без пропускане"),
+    (Some(3), "До 3 пропускания"),
+    (Some(5), "До 5 пропускания"),
+    (Some(10), "До 10 пропускания"),
+];
+
 pub enum AssignmentMessage {
     State(Rc<Assignments>),
 }
@@ -95,7 +124,7 @@ pub struct AssignmentCardProps {
 
 pub struct AssignmentCard {
     state: Rc<Assignments>,
-    _dispatch: Dispatch<Assignments>,
+    dispatch: Dispatch<Assignments>,
     assignment: Option<Assignment>,
 }
 
@@ -110,7 +139,7 @@ impl Component for AssignmentCard {
         let assignment = state.get(ctx.props().assignment).cloned();
         Self {
             state,
-            _dispatch: dispatch,
+            dispatch,
             assignment,
         }
     }
@@ -132,22 +161,73 @@ impl Component for AssignmentCard {
                     <h2 class="w3-text-grey w3-padding-16">
                     <i class="fa fa-solid fa-calculator fa-fw w3-margin-right w3-xxlarge w3-text-teal"></i>
                         {assignment.title()}
+                        <button onclick={self.toggle_abacus(assignment)} class={classes!("w3-button", "w3-round", "w3-right", "w3-small", if assignment.abacus { "w3-teal" } else { "w3-light-grey" })}>
+                            <i class="fa fa-solid fa-ellipsis-vertical w3-margin-right"></i>{"Соробан"}
+                        </button>
+                        <select onchange={self.select_sampling(assignment)} class="w3-select w3-round w3-right w3-small w3-margin-right" style="width: auto">
+                            { for self.samplings(assignment).map(|(sampling, label)| html! {
+                                <option selected={*sampling == assignment.sampling()}>{label}</option>
+                            })}
+                        </select>
+                        <button onclick={self.toggle_retry(assignment)} class={classes!("w3-button", "w3-round", "w3-right", "w3-small", "w3-margin-right", if assignment.retry { "w3-teal" } else { "w3-light-grey" })}>
+                            <i class="fa fa-solid fa-repeat w3-margin-right"></i>{"До верен отговор"}
+                        </button>
+                        <button onclick={self.toggle_requeue(assignment)} class={classes!("w3-button", "w3-round", "w3-right", "w3-small", "w3-margin-right", if assignment.requeue_skipped { "w3-teal" } else { "w3-light-grey" })}>
+                            <i class="fa fa-solid fa-rotate-left w3-margin-right"></i>{"Повтори пропуснатите"}
+                        </button>
+                        <select onchange={self.select_max_skips(assignment)} class="w3-select w3-round w3-right w3-small w3-margin-right" style="width: auto">
+                            { for SKIP_LIMITS.iter().map(|(limit, label)| html! {
+                                <option selected={*limit == assignment.max_skips}>{label}</option>
+                            })}
+                        </select>
+                        // The seed can be shared so that another device gets
+                        // the same tasks, until the first task is answered.
+                        <input onchange={self.set_seed(assignment)} value={assignment.seed.to_string()} disabled={!assignment.tasks.is_empty()} title="Код на задачите" class="w3-input w3-round w3-right w3-small w3-margin-right" style="width: 14em" type="text"/>
+                        // Flash anzan has no countdown, its numbers already
+                        // come at their own pace.
+                        if !matches!(assignment.current_builder().mode, TaskBuilderMode::FlashAnzan { .. }) {
+                            <select onchange={self.select_task_limit(assignment)} class="w3-select w3-round w3-right w3-small w3-margin-right" style="width: auto">
+                                { for TASK_LIMITS.iter().map(|(limit, label)| html! {
+                                    <option selected={*limit == assignment.task_limit}>{label}</option>
+                                })}
+                            </select>
+                        }
                     </h2>
 
                     <div class="w3-container">
+                        if !assignment.sections.is_empty() {
+                            // SectionsView renders the progress of each section
+                            <SectionsView assignment_id={assignment.id}/>
+                        }
+                        // CriteriaView sets the completion criteria and shows which are met
+                        <CriteriaView assignment_id={assignment.id}/>
                         if !assignment.is_done() {
                             <div class="w3-display-container">
                                 <ul class="w3-ul w3-display-topright">
                                     // ProgressView renders the number of correct and wrong tasks
                                     <ProgressView progress={assignment.progress()}/>
                                 </ul>
+                                if assignment.time_limit.is_some() {
+                                    // Countdown renders the time left of a sprint and ends it
+                                    <div class="w3-display-topleft">
+                                        <Countdown assignment_id={assignment.id}/>
+                                    </div>
+                                }
 
-                                // TaskView renders the current task
-                                <TaskView assignment_id={assignment.id}/>
+                                if let TaskBuilderMode::FlashAnzan { interval, .. } = assignment.current_builder().mode {
+                                    // FlashView flashes the numbers of the current task one at a time
+                                    <FlashView assignment_id={assignment.id} {interval}/>
+                                } else {
+                                    // TaskView renders the current task
+                                    <TaskView assignment_id={assignment.id} abacus={assignment.abacus}/>
+                                }
                             </div>
                         }
+                        if let Some(summary) = assignment.sprint_summary() {
+                            <SprintSummaryView {summary}/>
+                        }
                         // TaskList renders the tasks that have been completed in the assignment
-                        <TaskList tasks={assignment.tasks.clone()} show_time={assignment.timed}/>
+                        <TaskList tasks={assignment.tasks.clone()} show_time={assignment.timed} show_abacus={assignment.abacus}/>
                     </div>
                     <hr/>
                 } else {
@@ -163,6 +243,93 @@ impl Component for AssignmentCard {
     }
 }
 
+impl AssignmentCard {
+    // The samplings the tasks of the assignment can use. Only some modes
+    // have a small set of facts to deal, and only some facts commute.
+    fn samplings<'a>(
+        &self,
+        assignment: &'a Assignment,
+    ) -> impl Iterator<Item = &'static (Sampling, &'static str)> + 'a {
+        let builder = assignment.current_builder();
+        SAMPLINGS
+            .iter()
+            .filter(move |(sampling, _)| match sampling {
+                Sampling::Deck { commutative } => {
+                    builder.can_deal() && (!commutative || builder.commutes())
+                }
+                _ => true,
+            })
+    }
+
+    fn select_sampling(&self, assignment: &Assignment) -> Callback<Event> {
+        let id = assignment.id;
+        let samplings: Vec<Sampling> = self.samplings(assignment).map(|(s, _)| *s).collect();
+        self.dispatch
+            .reduce_mut_callback_with(move |assignments, e: Event| {
+                let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+                if let Some(sampling) = samplings.get(select.selected_index().max(0) as usize) {
+                    assignments.set_sampling(id, *sampling);
+                }
+            })
+    }
+
+    fn select_task_limit(&self, assignment: &Assignment) -> Callback<Event> {
+        let id = assignment.id;
+        self.dispatch
+            .reduce_mut_callback_with(move |assignments, e: Event| {
+                let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+                if let Some((limit, _)) = TASK_LIMITS.get(select.selected_index().max(0) as usize) {
+                    assignments.set_task_limit(id, *limit);
+                }
+            })
+    }
+
+    fn select_max_skips(&self, assignment: &Assignment) -> Callback<Event> {
+        let id = assignment.id;
+        let requeue = assignment.requeue_skipped;
+        self.dispatch
+            .reduce_mut_callback_with(move |assignments, e: Event| {
+                let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+                if let Some((limit, _)) = SKIP_LIMITS.get(select.selected_index().max(0) as usize) {
+                    assignments.set_skips(id, *limit, requeue);
+                }
+            })
+    }
+
+    fn toggle_requeue(&self, assignment: &Assignment) -> Callback<MouseEvent> {
+        let id = assignment.id;
+        let max_skips = assignment.max_skips;
+        let requeue = !assignment.requeue_skipped;
+        self.dispatch
+            .reduce_mut_callback(move |assignments| assignments.set_skips(id, max_skips, requeue))
+    }
+
+    fn set_seed(&self, assignment: &Assignment) -> Callback<Event> {
+        let id = assignment.id;
+        self.dispatch
+            .reduce_mut_callback_with(move |assignments, e: Event| {
+                let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+                if let Ok(seed) = input.value().trim().parse() {
+                    assignments.set_seed(id, seed);
+                }
+            })
+    }
+
+    fn toggle_retry(&self, assignment: &Assignment) -> Callback<MouseEvent> {
+        let id = assignment.id;
+        let retry = !assignment.retry;
+        self.dispatch
+            .reduce_mut_callback(move |assignments| assignments.set_retry(id, retry))
+    }
+
+    fn toggle_abacus(&self, assignment: &Assignment) -> Callback<MouseEvent> {
+        let id = assignment.id;
+        let abacus = !assignment.abacus;
+        self.dispatch
+            .reduce_mut_callback(move |assignments| assignments.set_abacus(id, abacus))
+    }
+}
+
 #[derive(PartialEq, Properties)]
 struct ProgressViewProps {
     progress: Progress,
@@ -178,65 +345,595 @@ fn ProgressView(ProgressViewProps { progress }: &ProgressViewProps) -> Html {
             <div class="w3-bar-item w3-padding-small">
                 <i class="fa fa-solid fa-circle-xmark w3-round w3-padding w3-red">{format!(" {}", progress.wrong)}</i>
             </div>
+            if progress.retries > 0 {
+                <div class="w3-bar-item w3-padding-small">
+                    <i class="fa fa-solid fa-rotate-left w3-round w3-padding w3-amber">{format!(" {}", progress.retries)}</i>
+                </div>
+            }
+            if progress.skipped > 0 {
+                <div class="w3-bar-item w3-padding-small">
+                    <i class="fa fa-solid fa-share w3-round w3-padding w3-gray">{format!(" {}", progress.skipped)}</i>
+                </div>
+            }
+            if progress.timed_out > 0 {
+                <div class="w3-bar-item w3-padding-small">
+                    <i class="fa fa-solid fa-hourglass-end w3-round w3-padding w3-orange">{format!(" {}", progress.timed_out)}</i>
+                </div>
+            }
         </li>
     }
 }
 
+#[derive(PartialEq, Properties)]
+struct SectionsViewProps {
+    assignment_id: Uuid,
+}
+
+#[function_component]
+fn SectionsView(SectionsViewProps { assignment_id }: &SectionsViewProps) -> Html {
+    let (assignments, _) = use_store::<Assignments>();
+    let assignment = assignments
+        .get(*assignment_id)
+        .expect("invalid assignment id");
+    let current = assignment.section();
+    html! {
+        <ul class="w3-ul w3-margin-bottom">
+            { for assignment.sections.iter().zip(assignment.section_progress()).enumerate().map(|(i, (section, progress))| html! {
+                <li class={classes!("w3-row", (i == current && !assignment.is_done()).then_some("w3-pale-green"))}>
+                    <div class="w3-col s4">{&section.title}</div>
+                    <div class="w3-col s6">
+                        <div class="w3-light-grey w3-round-xlarge w3-small">
+                            <div class="w3-container w3-center w3-round-xlarge w3-teal" style={format!("width: {}%", progress.percent_done)}>{format!("{}%", progress.percent_done)}</div>
+                        </div>
+                    </div>
+                    <div class="w3-col s2 w3-right-align w3-small">
+                        {format!("{} ✓ {} ✗ / {}", progress.correct, progress.wrong, section.num_tasks)}
+                    </div>
+                </li>
+            })}
+        </ul>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+struct CriteriaViewProps {
+    assignment_id: Uuid,
+}
+
+#[function_component]
+fn CriteriaView(CriteriaViewProps { assignment_id }: &CriteriaViewProps) -> Html {
+    let (assignments, dispatch) = use_store::<Assignments>();
+    let assignment = assignments
+        .get(*assignment_id)
+        .expect("invalid assignment id");
+    let criteria = assignment.criteria;
+    // The criteria can't be changed once tasks are answered against them.
+    let locked = !assignment.tasks.is_empty();
+    let update = |change: fn(&mut Criteria, usize)| {
+        let id = *assignment_id;
+        dispatch.reduce_mut_callback_with(move |s, e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            let mut criteria = criteria;
+            change(&mut criteria, select.selected_index().max(0) as usize);
+            s.set_criteria(id, criteria);
+        })
+    };
+    let option = |label: String, selected: bool| {
+        html! {
+            <option {selected}>{label}</option>
+        }
+    };
+
+    html! {
+        <div class="w3-row-padding w3-small w3-margin-bottom">
+            <div class="w3-quarter">
+                <select class="w3-select" disabled={locked} onchange={update(|c, i| c.min_accuracy = MIN_ACCURACY[i])}>
+                    { for MIN_ACCURACY.iter().map(|min| option(match min {
+                        Some(min) => format!("Точност поне {}%", min),
+                        None => "Без изискване за точност".to_owned(),
+                    }, *min == criteria.min_accuracy))}
+                </select>
+            </div>
+            <div class="w3-quarter">
+                <select class="w3-select" disabled={locked} onchange={update(|c, i| c.max_average_secs = MAX_AVERAGE_SECS[i])}>
+                    { for MAX_AVERAGE_SECS.iter().map(|max| option(match max {
+                        Some(max) => format!("Средно до {} сек.", max),
+                        None => "Без изискване за време".to_owned(),
+                    }, *max == criteria.max_average_secs))}
+                </select>
+            </div>
+            <div class="w3-quarter">
+                <select class="w3-select" disabled={locked} onchange={update(|c, i| c.max_wrong = MAX_WRONG[i])}>
+                    { for MAX_WRONG.iter().map(|max| option(match max {
+                        Some(max) => format!("До {} грешни", max),
+                        None => "Без изискване за грешки".to_owned(),
+                    }, *max == criteria.max_wrong))}
+                </select>
+            </div>
+            <div class="w3-quarter">
+                <select class="w3-select" disabled={locked} onchange={update(|c, i| c.on_failure = if i == 0 { OnFailure::Extend } else { OnFailure::Restart })}>
+                    {option("При неуспех продължи".to_owned(), criteria.on_failure == OnFailure::Extend)}
+                    {option("При неуспех започни отначало".to_owned(), criteria.on_failure == OnFailure::Restart)}
+                </select>
+            </div>
+            if !criteria.is_empty() {
+                <div class="w3-col s12 w3-margin-top">
+                    { for assignment.check_criteria().into_iter().map(|criterion| {
+                        let text = match criterion {
+                            Criterion::Accuracy { min, actual } => format!("точност {}% (поне {}%)", actual, min),
+                            Criterion::AverageTime { max, actual } => format!("средно {:.1} сек. (до {} сек.)", actual, max),
+                            Criterion::Wrong { max, actual } => format!("{} грешни (до {})", actual, max),
+                        };
+                        html! {
+                            <span class={classes!("w3-tag", "w3-round", "w3-margin-right", if criterion.met() { "w3-teal" } else { "w3-red" })}>
+                                <i class={classes!("fa", "fa-solid", "w3-margin-right", if criterion.met() { "fa-check" } else { "fa-xmark" })}></i>
+                                {text}
+                            </span>
+                        }
+                    })}
+                </div>
+            }
+        </div>
+    }
+}
+
+// The options offered for each completion criterion, `None` for no criterion.
+const MIN_ACCURACY: [Option<u32>; 5] = [None, Some(70), Some(80), Some(90), Some(100)];
+const MAX_AVERAGE_SECS: [Option<u32>; 5] = [None, Some(3), Some(5), Some(10), Some(20)];
+const MAX_WRONG: [Option<u32>; 5] = [None, Some(0), Some(3), Some(5), Some(10)];
+
+#[derive(PartialEq, Properties)]
+struct CountdownProps {
+    assignment_id: Uuid,
+}
+
+#[function_component]
+fn Countdown(CountdownProps { assignment_id }: &CountdownProps) -> Html {
+    let (assignments, dispatch) = use_store::<Assignments>();
+    let assignment = assignments
+        .get(*assignment_id)
+        .expect("invalid assignment id");
+    let now = use_state(|| Utc::now().timestamp_millis());
+    {
+        let now = now.clone();
+        use_interval(move || now.set(Utc::now().timestamp_millis()), 250);
+    }
+    let left = assignment.time_left(*now).unwrap_or_default();
+    {
+        // The sprint is over once the time runs out, even mid-task.
+        let id = *assignment_id;
+        use_effect_with_deps(
+            move |over| {
+                if *over {
+                    dispatch.reduce_mut(|s| s.expire(id));
+                }
+            },
+            left == 0,
+        );
+    }
+    let seconds = (left + 999) / 1000;
+    html! {
+        <div class={classes!("w3-tag", "w3-round", "w3-large", if seconds > 10 { "w3-teal" } else { "w3-red" })}>
+            <i class="fa fa-solid fa-stopwatch w3-margin-right"></i>
+            {format!("{}:{:02}", seconds / 60, seconds % 60)}
+        </div>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+struct SprintSummaryViewProps {
+    summary: SprintSummary,
+}
+
+#[function_component]
+fn SprintSummaryView(SprintSummaryViewProps { summary }: &SprintSummaryViewProps) -> Html {
+    html! {
+        <div class="w3-panel w3-pale-green w3-leftbar w3-border-green w3-padding">
+            <p class="w3-large"><b>{"Край на спринта!"}</b></p>
+            <p>
+                {format!("{} верни от {} примера", summary.correct, summary.answered)}<br/>
+                {format!("{:.1} верни на минута", summary.per_minute)}<br/>
+                {format!("{}% точност", summary.accuracy)}
+            </p>
+        </div>
+    }
+}
+
 #[derive(PartialEq, Properties)]
 struct TaskViewProps {
     assignment_id: Uuid,
+    abacus: bool,
 }
 
 #[function_component]
-fn TaskView(TaskViewProps { assignment_id }: &TaskViewProps) -> Html {
+fn TaskView(
+    TaskViewProps {
+        assignment_id,
+        abacus,
+    }: &TaskViewProps,
+) -> Html {
     let (assignments, dispatch) = use_store::<Assignments>();
-    let next_task = assignments
-        .get(assignment_id.clone())
+    let pending = assignments
+        .get(*assignment_id)
         .expect("invalid assignment id")
-        .task();
+        .pending
+        .clone();
+    {
+        // The pending task is built, and its clock started, once it is shown.
+        let id = *assignment_id;
+        let dispatch = dispatch.clone();
+        use_effect_with_deps(
+            move |started| {
+                if !*started {
+                    dispatch.reduce_mut(|s| s.show_task(id));
+                }
+            },
+            pending.as_ref().map_or(false, |t| t.t_start.is_some()),
+        );
+    }
+    let task_limit = assignments
+        .get(*assignment_id)
+        .and_then(|a| a.current_task_limit());
+    let skips_left = assignments.get(*assignment_id).and_then(|a| a.skips_left());
+    let now = use_state(|| Utc::now().timestamp_millis());
+    {
+        let now = now.clone();
+        use_interval(
+            move || now.set(Utc::now().timestamp_millis()),
+            if task_limit.is_some() { 100 } else { 0 },
+        );
+    }
+    let time_left = assignments
+        .get(*assignment_id)
+        .and_then(|a| a.task_time_left(*now));
+    {
+        // A task that runs out of time is skipped for the next one.
+        let id = *assignment_id;
+        let dispatch = dispatch.clone();
+        use_effect_with_deps(
+            move |timed_out| {
+                if *timed_out {
+                    dispatch.reduce_mut(|s| s.time_out(id));
+                }
+            },
+            time_left == Some(0),
+        );
+    }
+    let answer_ref = use_node_ref();
+    let remainder_ref = use_node_ref();
+    let Some(next_task) = pending else {
+        return html! {};
+    };
     let onkeypress = {
-        let id = assignment_id.clone();
+        let id = *assignment_id;
+        let answer_ref = answer_ref.clone();
+        let remainder_ref = remainder_ref.clone();
+        let solution = next_task.solution();
         dispatch.reduce_mut_callback_with(move |s, e: KeyboardEvent| {
-            let mut task = next_task.clone();
             if e.key() == "Enter" {
-                let input: web_sys::HtmlInputElement = e.target_unchecked_into();
-                let answer = input.value().parse::<i32>().ok();
-                input.set_value("");
-                task.answer = answer;
-                task.t_finish = Some(Utc::now().timestamp_millis());
-                s.submit_task(id, task);
+                let answer = match solution {
+                    Answer::Number(_) => read_number(&answer_ref).map(Answer::Number),
+                    Answer::Operator(_) => read_operator(&answer_ref).map(Answer::Operator),
+                    Answer::Remainder { .. } => {
+                        match (read_number(&answer_ref), read_number(&remainder_ref)) {
+                            (Some(quotient), Some(remainder)) => Some(Answer::Remainder {
+                                quotient,
+                                remainder,
+                            }),
+                            _ => None,
+                        }
+                    }
+                };
+                // Until the answer can be read, Enter does nothing; skipping
+                // is an explicit action.
+                let Some(answer) = answer else {
+                    return;
+                };
+                clear_input(&answer_ref);
+                clear_input(&remainder_ref);
+                if let Some(input) = answer_ref.cast::<web_sys::HtmlInputElement>() {
+                    let _ = input.focus();
+                }
+                s.answer_task(id, answer);
             };
         })
     };
+    let can_skip = skips_left != Some(0);
+    let onskip = {
+        let id = *assignment_id;
+        let answer_ref = answer_ref.clone();
+        let remainder_ref = remainder_ref.clone();
+        dispatch.reduce_mut_callback(move |s| {
+            clear_input(&answer_ref);
+            clear_input(&remainder_ref);
+            s.skip(id);
+        })
+    };
+    // Escape skips the task from the keyboard.
+    let onkeydown = {
+        let onskip = onskip.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            if e.key() == "Escape" && can_skip {
+                onskip.emit(());
+            }
+        })
+    };
+    let onskip = onskip.reform(|_: MouseEvent| ());
+
+    let placeholder = match next_task.unknown {
+        Unknown::Operator => "Кой знак? (+ - x :)",
+        _ => "Колко получи?",
+    };
+
+    html! {
+        <div class="w3-container w3-text-teal w3-center w3-content w3-margin-right w3-margin-left">
+            if let (Some(limit), Some(left)) = (task_limit, time_left) {
+                <div class="w3-light-grey w3-round w3-margin-top">
+                    <div class="w3-container w3-round w3-teal" style={format!("width: {}%; height: 6px", left / (limit as i64 * 10))}></div>
+                </div>
+            }
+            if next_task.prompt == Prompt::Soroban {
+                <p><Soroban value={next_task.x}/></p>
+            } else if *abacus {
+                <AbacusExpression task={next_task.clone()}/>
+            } else {
+                <p class={if next_task.chain.is_empty() { "w3-jumbo" } else { "w3-xxxlarge" }}><b>{next_task.clone()}</b></p>
+            }
+            if let Answer::Remainder { .. } = next_task.solution() {
+                <div class="w3-row w3-margin-bottom">
+                    <div class="w3-col s5">
+                        <input ref={answer_ref} placeholder="Частно" class="w3-input" type="text" onkeypress={onkeypress.clone()} onkeydown={onkeydown.clone()}/>
+                    </div>
+                    <div class="w3-col s2 w3-padding">{"ост."}</div>
+                    <div class="w3-col s5">
+                        <input ref={remainder_ref} placeholder="Остатък" class="w3-input" type="text" {onkeypress} {onkeydown}/>
+                    </div>
+                </div>
+            } else {
+                <p><input ref={answer_ref} placeholder={placeholder} class="w3-input" type="text" {onkeypress} {onkeydown}/></p>
+            }
+            if let Some(wrong) = next_task.answer() {
+                <p class="w3-text-red">{format!("{} не е вярно, опитай пак!", wrong)}</p>
+            }
+            <p>
+                <button onclick={onskip} disabled={!can_skip} class="w3-button w3-light-grey w3-round w3-small" title="Esc">
+                    <i class="fa fa-solid fa-share w3-margin-right"></i>{"Пропусни"}
+                    if let Some(left) = skips_left {
+                        {format!(" ({})", left)}
+                    }
+                </button>
+            </p>
+        </div>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+struct AbacusExpressionProps {
+    task: Task,
+}
+
+// Renders the operands of the task as sorobans joined by the operators.
+#[function_component]
+fn AbacusExpression(AbacusExpressionProps { task }: &AbacusExpressionProps) -> Html {
+    // The unknown operand or operator, if any, is left out as a question mark.
+    let slot = |value: i32, unknown: Unknown| (task.unknown != unknown).then_some(value);
+    let op = match task.unknown {
+        Unknown::Operator => "?".to_owned(),
+        _ => task.op.to_string(),
+    };
+    let operands = [
+        (None, slot(task.x, Unknown::X)),
+        (Some(op), slot(task.y, Unknown::Y)),
+    ]
+    .into_iter()
+    .chain(
+        task.chain
+            .iter()
+            .map(|term| (Some(term.op.to_string()), Some(term.value))),
+    );
+    html! {
+        <p class="w3-xxlarge">
+            { for operands.map(|(op, value)| html! {
+                <>
+                    if let Some(op) = op {
+                        <b class="w3-margin">{op}</b>
+                    }
+                    if let Some(value) = value {
+                        <Soroban {value}/>
+                    } else {
+                        <b class="w3-margin">{"?"}</b>
+                    }
+                </>
+            })}
+            <b class="w3-margin">{"="}</b>
+            if task.unknown == Unknown::Result {
+                <b class="w3-margin">{"?"}</b>
+            } else {
+                <Soroban value={task.value()}/>
+            }
+        </p>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+struct FlashViewProps {
+    assignment_id: Uuid,
+    interval: u32,
+}
+
+#[function_component]
+fn FlashView(
+    FlashViewProps {
+        assignment_id,
+        interval,
+    }: &FlashViewProps,
+) -> Html {
+    let (assignments, dispatch) = use_store::<Assignments>();
+    // The pending task is only flashed after the start button is pressed.
+    let started = use_state(|| false);
+    let task = assignments
+        .get(*assignment_id)
+        .expect("invalid assignment id")
+        .pending
+        .clone()
+        .filter(|_| *started);
+    let skips_left = assignments.get(*assignment_id).and_then(|a| a.skips_left());
+    let shown = use_state(|| 0_usize);
+    let terms = task.as_ref().map(|t| t.terms()).unwrap_or_default();
+    let flashing = task.is_some() && *shown < terms.len();
+    {
+        let shown = shown.clone();
+        use_interval(
+            move || shown.set(*shown + 1),
+            if flashing { *interval } else { 0 },
+        );
+    }
+    let answer_ref = use_node_ref();
+    {
+        let answer_ref = answer_ref.clone();
+        use_effect_with_deps(
+            move |_| {
+                if let Some(input) = answer_ref.cast::<web_sys::HtmlInputElement>() {
+                    let _ = input.focus();
+                }
+            },
+            flashing,
+        );
+    }
+
+    let onstart = {
+        let id = *assignment_id;
+        let started = started.clone();
+        let shown = shown.clone();
+        dispatch.reduce_mut_callback(move |s| {
+            s.show_task(id);
+            started.set(true);
+            shown.set(0);
+        })
+    };
+    let onkeypress = {
+        let id = *assignment_id;
+        let task = task.clone();
+        let started = started.clone();
+        let answer_ref = answer_ref.clone();
+        dispatch.reduce_mut_callback_with(move |s, e: KeyboardEvent| {
+            if let (Some(_), "Enter") = (task.as_ref(), e.key().as_str()) {
+                // Until the answer can be read, Enter does nothing; skipping
+                // is an explicit action.
+                let Some(answer) = read_number(&answer_ref) else {
+                    return;
+                };
+                s.answer_task(id, Answer::Number(answer));
+                clear_input(&answer_ref);
+                // A retried task is flashed again from the start.
+                started.set(false);
+            }
+        })
+    };
+    let can_skip = skips_left != Some(0);
+    let onskip = {
+        let id = *assignment_id;
+        let started = started.clone();
+        let answer_ref = answer_ref.clone();
+        dispatch.reduce_mut_callback(move |s| {
+            clear_input(&answer_ref);
+            s.skip(id);
+            started.set(false);
+        })
+    };
+    // Escape skips the task from the keyboard.
+    let onkeydown = {
+        let onskip = onskip.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            if e.key() == "Escape" && can_skip {
+                onskip.emit(());
+            }
+        })
+    };
+    let onskip = onskip.reform(|_: MouseEvent| ());
 
     html! {
         <div class="w3-container w3-text-teal w3-center w3-content w3-margin-right w3-margin-left">
-            <p class="w3-jumbo"><b>{next_task}</b></p>
-            <p><input placeholder="Колко получи?" class="w3-input" type="text" {onkeypress}/></p>
+            if task.is_none() {
+                <p class="w3-jumbo"><b>{"?"}</b></p>
+                <p><button onclick={onstart} class="w3-button w3-teal w3-round w3-padding">{"Старт"}</button></p>
+            } else if flashing {
+                // Keyed by position, so that repeated numbers flash again
+                <p key={*shown} class="w3-jumbo w3-animate-opacity"><b>{terms[*shown]}</b></p>
+            } else {
+                <p class="w3-jumbo"><b>{"= ?"}</b></p>
+                <p><input ref={answer_ref} placeholder="Колко получи?" class="w3-input" type="text" {onkeypress} {onkeydown}/></p>
+                <p>
+                    <button onclick={onskip} disabled={!can_skip} class="w3-button w3-light-grey w3-round w3-small" title="Esc">
+                        <i class="fa fa-solid fa-share w3-margin-right"></i>{"Пропусни"}
+                        if let Some(left) = skips_left {
+                            {format!(" ({})", left)}
+                        }
+                    </button>
+                </p>
+            }
         </div>
     }
 }
 
+// Reads the operator typed into the referenced input.
+fn read_operator(input: &NodeRef) -> Option<Operation> {
+    let input = input.cast::<web_sys::HtmlInputElement>()?;
+    input.value().parse::<Operation>().ok()
+}
+
+// Reads the number typed into the referenced input.
+fn read_number(input: &NodeRef) -> Option<i32> {
+    let input = input.cast::<web_sys::HtmlInputElement>()?;
+    input.value().trim().parse::<i32>().ok()
+}
+
+fn clear_input(input: &NodeRef) {
+    if let Some(input) = input.cast::<web_sys::HtmlInputElement>() {
+        input.set_value("");
+    }
+}
+
 #[derive(Properties, PartialEq)]
 pub struct TaskListProps {
     tasks: Vec<Task>,
     show_time: bool,
+    #[prop_or_default]
+    show_abacus: bool,
 }
 
 #[function_component]
-pub fn TaskList(TaskListProps { tasks, show_time }: &TaskListProps) -> Html {
+pub fn TaskList(
+    TaskListProps {
+        tasks,
+        show_time,
+        show_abacus,
+    }: &TaskListProps,
+) -> Html {
     html! {
         <ul class="w3-ul">
-            { for tasks.iter().rev().enumerate().map(|(i, task)| {
-                let effects = if i ==0 {
+            // Newest first, keyed by the position in `tasks` so that every
+            // row keeps its state, e.g. the beads of its soroban, when a new
+            // task is added on top.
+            { for tasks.iter().enumerate().rev().map(|(i, task)| {
+                let effects = if i + 1 == tasks.len() {
                     classes!("w3-animate-right")
                 } else {
                     classes!()
                 };
                 html! {
-                    <li class={classes!("w3-bar", effects)}>
+                    <li key={i} class={classes!("w3-bar", effects)}>
                         {task.state().icon()}
                         <div class="w3-bar-item w3-center">{ task }</div>
+                        if task.attempts.len() > 1 {
+                            <span class="w3-bar-item w3-tag w3-round w3-amber w3-small">{format!("{} опита", task.attempts.len())}</span>
+                        }
+                        if *show_abacus && !task.correct() {
+                            // The beads slide from the given answer to the correct one
+                            if let (Some(Answer::Number(given)), Answer::Number(correct)) = (task.answer(), task.solution()) {
+                                <div class="w3-bar-item"><Soroban value={correct} from={given} small={true}/></div>
+                            }
+                        }
                         if *show_time  {
                             if let (Some(start), Some(finish)) = (task.t_start, task.t_finish) {
                                 <i class="w3-bar-item w3-round w3-right fa fa-solid fa-stopwatch w3-light-gray">{format!(" {:.2}", (finish - start) as f32/1000_f32)}</i>
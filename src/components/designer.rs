@@ -0,0 +1,267 @@
+use yew::prelude::*;
+use yewdux::prelude::use_store;
+
+use crate::{
+    components::{assignment::TASK_LIMITS, free::DigitsSelect},
+    model::{
+        operand::OperandRange,
+        preset::{Design, Preset},
+        task::{Operation, Task},
+        ui::{Assignments, Presets},
+    },
+};
+
+// The operations offered by the designer. Addition also brings subtraction.
+const OPERATIONS: [(Operation, &str); 4] = [
+    (Operation::Multiplication, "x"),
+    (Operation::Addition, "+/-"),
+    (Operation::Division, ":"),
+    (Operation::DivisionWithRemainder, ": с остатък"),
+];
+
+// The number of sample tasks shown before adding.
+const PREVIEW_COUNT: usize = 5;
+
+/// A form for building a free play assignment from scratch and saving it as
+/// a preset.
+#[function_component]
+pub fn AssignmentDesigner() -> Html {
+    let (_, dispatch) = use_store::<Assignments>();
+    let (_, presets) = use_store::<Presets>();
+    let design = use_state(Design::default);
+    let name = use_state(String::new);
+    let preview = use_state(Vec::<Task>::new);
+
+    let update = |f: fn(&mut Design, i32)| {
+        let design = design.clone();
+        let preview = preview.clone();
+        Callback::from(move |value: i32| {
+            let mut next = (*design).clone();
+            f(&mut next, value);
+            design.set(next);
+            preview.set(Vec::new());
+        })
+    };
+    let number_input = |value: i32, onchange: Callback<i32>| {
+        let onchange = onchange.reform(move |e: Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            input.value().parse().unwrap_or(value)
+        });
+        html! {
+            <input class="w3-input" type="number" min="0" value={value.to_string()} {onchange}/>
+        }
+    };
+
+    // The buttons stay disabled while the design can't build its tasks.
+    let error = design.builder().err();
+    let valid = error.is_none() && design.num_tasks > 0;
+
+    let show_preview = {
+        let design = design.clone();
+        let preview = preview.clone();
+        Callback::from(move |_| {
+            if let Ok(tasks) = design.preview(rand::random(), PREVIEW_COUNT) {
+                preview.set(tasks);
+            }
+        })
+    };
+    let title = |name: &str| {
+        if name.trim().is_empty() {
+            "Моя Игра".to_owned()
+        } else {
+            name.trim().to_owned()
+        }
+    };
+    let add = {
+        let design = (*design).clone();
+        let title = title(&name);
+        dispatch.reduce_mut_callback(move |assignments| {
+            if let Ok(assignment) = design.assignment(title.clone(), None) {
+                assignments.push(assignment);
+            }
+        })
+    };
+    let save = {
+        let design = (*design).clone();
+        let title = title(&name);
+        presets.reduce_mut_callback(move |presets| {
+            presets.push(Preset {
+                name: title.clone(),
+                design: design.clone(),
+            });
+        })
+    };
+    let select_task_limit = {
+        let design = design.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            if let Some((limit, _)) = TASK_LIMITS.get(select.selected_index().max(0) as usize) {
+                let mut next = (*design).clone();
+                next.task_limit = *limit;
+                design.set(next);
+            }
+        })
+    };
+    let toggle_timed = {
+        let design = design.clone();
+        Callback::from(move |_| {
+            let mut next = (*design).clone();
+            next.timed = !next.timed;
+            design.set(next);
+        })
+    };
+    let set_name = {
+        let name = name.clone();
+        Callback::from(move |e: Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            name.set(input.value());
+        })
+    };
+
+    html! {
+        <div class="w3-margin-bottom">
+            <ul class="w3-ul w3-border w3-center w3-hover-shadow">
+                <li class="w3-large w3-padding-16 w3-text-grey"><i
+                        class="fa fa-pen-ruler fa-fw w3-margin-right w3-xlarge w3-text-teal"></i>{"Моя Игра"}</li>
+                <li class="w3-padding-16 w3-small">
+                    {design.description()}
+                    <div class="w3-bar w3-margin-top">
+                        { for OPERATIONS.iter().map(|(op, label)| {
+                            let op = *op;
+                            let selected = design.operations.contains(&op);
+                            let onclick = {
+                                let design = design.clone();
+                                let preview = preview.clone();
+                                Callback::from(move |_| {
+                                    let mut next = (*design).clone();
+                                    next.operations.retain(|o| *o != op);
+                                    if !selected {
+                                        next.operations.push(op);
+                                    }
+                                    design.set(next);
+                                    preview.set(Vec::new());
+                                })
+                            };
+                            html! {
+                                <button {onclick} class={classes!("w3-bar-item", "w3-button", "w3-round", if selected { "w3-teal" } else { "w3-light-grey" })}>{*label}</button>
+                            }
+                        })}
+                    </div>
+                    <div class="w3-row-padding w3-margin-top">
+                        <div class="w3-third">
+                            <DigitsSelect value={design.xrange.digit_count()} onchange={
+                                let design = design.clone();
+                                let preview = preview.clone();
+                                Callback::from(move |digits| {
+                                    let mut next = (*design).clone();
+                                    next.xrange = OperandRange::digits(digits);
+                                    design.set(next);
+                                    preview.set(Vec::new());
+                                })
+                            }/>
+                        </div>
+                        <div class="w3-third">{number_input(design.xrange.min, update(|d, v| d.xrange.min = v))}</div>
+                        <div class="w3-third">{number_input(design.xrange.max, update(|d, v| d.xrange.max = v))}</div>
+                    </div>
+                    <div class="w3-row-padding w3-margin-top">
+                        <div class="w3-third">
+                            <DigitsSelect value={design.yrange.digit_count()} onchange={
+                                let design = design.clone();
+                                let preview = preview.clone();
+                                Callback::from(move |digits| {
+                                    let mut next = (*design).clone();
+                                    next.yrange = OperandRange::digits(digits);
+                                    design.set(next);
+                                    preview.set(Vec::new());
+                                })
+                            }/>
+                        </div>
+                        <div class="w3-third">{number_input(design.yrange.min, update(|d, v| d.yrange.min = v))}</div>
+                        <div class="w3-third">{number_input(design.yrange.max, update(|d, v| d.yrange.max = v))}</div>
+                    </div>
+                    <div class="w3-row-padding w3-margin-top">
+                        <div class="w3-third">{number_input(design.num_tasks, update(|d, v| d.num_tasks = v))}</div>
+                        <div class="w3-third">
+                            <select class="w3-select" onchange={select_task_limit}>
+                                { for TASK_LIMITS.iter().map(|(limit, label)| html! {
+                                    <option selected={*limit == design.task_limit}>{label}</option>
+                                })}
+                            </select>
+                        </div>
+                        <div class="w3-third">
+                            <button onclick={toggle_timed} class={classes!("w3-button", "w3-round", if design.timed { "w3-teal" } else { "w3-light-grey" })}>
+                                <i class="fa fa-solid fa-stopwatch"></i>{" Време"}
+                            </button>
+                        </div>
+                    </div>
+                </li>
+                if let Some(err) = error {
+                    <li class="w3-padding-16 w3-small w3-pale-red">{err.to_string()}</li>
+                }
+                if !preview.is_empty() {
+                    <li class="w3-padding-16 w3-small">
+                        { for preview.iter().map(|task| html! { <div>{task}</div> }) }
+                    </li>
+                }
+                <li class="w3-padding-16 w3-small">
+                    <input class="w3-input" type="text" placeholder="Име" value={(*name).clone()} onchange={set_name}/>
+                </li>
+                <li class="w3-light-grey w3-padding-16">
+                    <button onclick={show_preview} disabled={!valid} class="w3-button w3-light-grey w3-round w3-padding w3-margin-right">{"Примери"}</button>
+                    <button onclick={save} disabled={!valid} class="w3-button w3-teal w3-round w3-padding w3-margin-right">{"Запази"}</button>
+                    <button onclick={add} disabled={!valid} class="w3-button w3-teal w3-round w3-padding">{"Добави"}</button>
+                </li>
+            </ul>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct PresetCardProps {
+    pub preset: Preset,
+}
+
+/// A card adding the assignment of a saved preset, like the built-in games.
+#[function_component]
+pub fn PresetCard(PresetCardProps { preset }: &PresetCardProps) -> Html {
+    let (_, dispatch) = use_store::<Assignments>();
+    let (_, presets) = use_store::<Presets>();
+    let assignment = preset.design.assignment(preset.name.clone(), None);
+    let error = assignment.as_ref().err().copied();
+    let add = {
+        let assignment = assignment.ok();
+        dispatch.reduce_mut_callback(move |assignments| {
+            if let Some(assignment) = &assignment {
+                let mut assignment = assignment.clone();
+                assignment.id = uuid::Uuid::new_v4();
+                assignment.seed = rand::random();
+                assignments.push(assignment);
+            }
+        })
+    };
+    let remove = {
+        let name = preset.name.clone();
+        presets.reduce_mut_callback(move |presets| presets.remove(&name))
+    };
+    html! {
+        <div class="w3-half w3-margin-bottom">
+            <ul class="w3-ul w3-border w3-center w3-hover-shadow">
+                <li class="w3-large w3-padding-16 w3-text-grey"><i
+                        class="fa fa-star fa-fw w3-margin-right w3-xlarge w3-text-teal"></i>{&preset.name}</li>
+                <li class="w3-padding-16 w3-small">
+                    {preset.design.description()}
+                    if let Some(err) = error {
+                        <div class="w3-pale-red">{err.to_string()}</div>
+                    }
+                </li>
+                <li class="w3-padding-16 w3-small"><b>{preset.design.num_tasks}</b> {"примера"} </li>
+                <li class="w3-light-grey w3-padding-16">
+                    <button onclick={add} disabled={error.is_some()} class="w3-button w3-teal w3-round w3-padding w3-margin-right">{"Добави"}</button>
+                    <button onclick={remove} class="w3-button w3-round w3-padding">
+                        <i class="fa fa-solid fa-trash-can w3-text-red w3-large"></i>
+                    </button>
+                </li>
+            </ul>
+        </div>
+    }
+}
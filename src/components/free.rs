@@ -1,10 +1,26 @@
+use std::ops::RangeInclusive;
+
 use uuid::Uuid;
 use yew::prelude::*;
-use yewdux::prelude::use_store;
+use yewdux::prelude::{use_store, Dispatch};
 
 use crate::{
-    components::assignment::AssignmentList,
-    model::{assignment::Assignment, ui::Assignments},
+    components::{
+        assignment::AssignmentList,
+        designer::{AssignmentDesigner, PresetCard},
+    },
+    model::{
+        assignment::{
+            addition_builder, addition_description, chain_description, complements_title,
+            digits_name, digits_plural, division_builder, flash_anzan_description, mix_description,
+            multiplication_builder, multiplication_description, sprint_description,
+            tables_description, unknowns_description, Assignment, Section,
+        },
+        config::ConfigError,
+        operand::Carry,
+        task::{TaskBuilderMode, UnknownWeights},
+        ui::{Assignments, Presets},
+    },
 };
 
 struct FreePlayCardState {
@@ -14,6 +30,7 @@ struct FreePlayCardState {
 #[function_component]
 pub fn FreePlayCard() -> Html {
     let (store, dispatch) = use_store::<Assignments>();
+    let (presets, _) = use_store::<Presets>();
     let assignments: Vec<Uuid> = store
         .assignments
         .iter()
@@ -21,12 +38,163 @@ pub fn FreePlayCard() -> Html {
         .map(|a| a.id)
         .collect();
 
-    let add_multiplication = dispatch.reduce_mut_callback(|assignments| {
-        assignments.push(Assignment::new_sd_sd_multiplication(100, None));
+    let error = use_state(|| None::<ConfigError>);
+
+    let multiplication_digits = use_state(|| (1, 1));
+    let add_multiplication = {
+        let (xdigits, ydigits) = *multiplication_digits;
+        add_assignment(&dispatch, &error, move || {
+            Assignment::new_multiplication(xdigits, ydigits, 100, None)
+        })
+    };
+
+    let tables = use_state(|| vec![7, 8]);
+    let add_tables = {
+        let tables = (*tables).clone();
+        add_assignment(&dispatch, &error, move || {
+            Assignment::new_times_tables(tables.clone(), 100, None)
+        })
+    };
+
+    let addition_digits = use_state(|| (1, 1));
+    let addition_carry = use_state(|| Carry::Any);
+    let add_addition = {
+        let (xdigits, ydigits) = *addition_digits;
+        let carry = *addition_carry;
+        add_assignment(&dispatch, &error, move || {
+            Assignment::new_addition(xdigits, ydigits, carry, 100, None)
+        })
+    };
+
+    let chain_terms = use_state(|| 5);
+    let chain_digits = use_state(|| 1);
+    let add_chain = {
+        let terms = *chain_terms;
+        let digits = *chain_digits;
+        add_assignment(&dispatch, &error, move || {
+            Assignment::new_chain(terms, digits, 100, None)
+        })
+    };
+
+    let flash_count = use_state(|| 5);
+    let flash_digits = use_state(|| 1);
+    let flash_interval = use_state(|| 1000);
+    let add_flash_anzan = {
+        let count = *flash_count;
+        let digits = *flash_digits;
+        let interval = *flash_interval;
+        add_assignment(&dispatch, &error, move || {
+            Assignment::new_flash_anzan(count, digits, interval, 10, None)
+        })
+    };
+
+    let reading_digits = use_state(|| 2);
+    let add_reading = {
+        let digits = *reading_digits;
+        add_assignment(&dispatch, &error, move || {
+            Assignment::new_soroban_reading(digits, 100, None)
+        })
+    };
+
+    let complements_target = use_state(|| 10);
+    let add_complements = {
+        let target = *complements_target;
+        add_assignment(&dispatch, &error, move || {
+            Assignment::new_complements(target, 100, None)
+        })
+    };
+
+    let missing_modes = [
+        (
+            "Събиране и Изваждане",
+            TaskBuilderMode::AdditionAndSubtraction,
+        ),
+        ("Умножение", TaskBuilderMode::Multiplication),
+        ("Деление", TaskBuilderMode::Division),
+    ];
+    let missing_unknowns = [
+        (
+            "число",
+            UnknownWeights {
+                result: 0,
+                x: 1,
+                y: 1,
+                operator: 0,
+            },
+        ),
+        (
+            "знак",
+            UnknownWeights {
+                result: 0,
+                x: 0,
+                y: 0,
+                operator: 1,
+            },
+        ),
+        (
+            "смесено",
+            UnknownWeights {
+                result: 1,
+                x: 1,
+                y: 1,
+                operator: 1,
+            },
+        ),
+    ];
+    let missing_mode = use_state(|| 1);
+    let missing_unknown = use_state(|| 0);
+    let add_missing = {
+        let mode = missing_modes[*missing_mode].1.clone();
+        let unknowns = missing_unknowns[*missing_unknown].1;
+        add_assignment(&dispatch, &error, move || {
+            Assignment::new_missing(mode.clone(), unknowns, 100, None)
+        })
+    };
+
+    let sprint_seconds = use_state(|| 60);
+    let add_sprint = {
+        let seconds = *sprint_seconds;
+        add_assignment(&dispatch, &error, move || {
+            Assignment::new_sprint(seconds, None)
+        })
+    };
+
+    let mix_weights = use_state(|| (50, 30, 20));
+    let add_mix = {
+        let (multiplication, addition, division) = *mix_weights;
+        add_assignment(&dispatch, &error, move || {
+            let parts = vec![
+                (multiplication, multiplication_builder(1, 1)?, "Умножение"),
+                (
+                    addition,
+                    addition_builder(1, 1, Carry::Any)?,
+                    "Събиране и Изваждане",
+                ),
+                (division, division_builder()?, "Деление"),
+            ];
+            Assignment::new_mix(parts, 100, None)
+        })
+    };
+
+    let add_composite = add_assignment(&dispatch, &error, || {
+        let sections = vec![
+            Section::new("Умножение", multiplication_builder(1, 1)?, 20),
+            Section::new(
+                "Събиране и Изваждане",
+                addition_builder(1, 1, Carry::Any)?,
+                20,
+            ),
+            Section::new("Деление", division_builder()?, 10),
+        ];
+        Assignment::new_composite("Смесено Домашно".to_owned(), sections, None)
     });
 
-    let add_addition = dispatch.reduce_mut_callback(|assignments| {
-        assignments.push(Assignment::new_sd_sd_addition(100, None));
+    let add_division = add_assignment(&dispatch, &error, || {
+        Assignment::new_sd_sd_division(100, None)
+    });
+
+    let add_remainder = add_assignment(&dispatch, &error, || {
+        Assignment::new_sd_sd_division_with_remainder(100, None)
     });
 
     let state = use_state(|| FreePlayCardState { show_modal: false });
@@ -52,13 +220,38 @@ pub fn FreePlayCard() -> Html {
                     <h4>{"Добави Игра"}</h4>
                 </header>
                 <div class="w3-container w3-padding-16">
+                    if let Some(err) = *error {
+                        <div class="w3-panel w3-pale-red w3-border w3-round">
+                            <p>{err.to_string()}</p>
+                        </div>
+                    }
                     <div class="w3-row-padding">
+                        <AssignmentDesigner/>
+                        { for presets.presets.iter().map(|preset| html! {
+                            <PresetCard preset={preset.clone()}/>
+                        })}
                         <div class="w3-half w3-margin-bottom">
                             <ul class="w3-ul w3-border w3-center w3-hover-shadow">
                                 <li class="w3-large w3-padding-16 w3-text-grey"><i
                                         class="fa fa-calculator fa-fw w3-margin-right w3-xlarge w3-text-teal"></i>{"Умножение"}
                                 </li>
-                                <li class="w3-padding-16 w3-small">{"едноцифрено по едноцифрено"}</li>
+                                <li class="w3-padding-16 w3-small">
+                                    {multiplication_description(multiplication_digits.0, multiplication_digits.1)}
+                                    <div class="w3-row-padding w3-margin-top">
+                                        <div class="w3-half">
+                                            <DigitsSelect value={multiplication_digits.0} onchange={
+                                                let digits = multiplication_digits.clone();
+                                                Callback::from(move |x| digits.set((x, digits.1)))
+                                            }/>
+                                        </div>
+                                        <div class="w3-half">
+                                            <DigitsSelect value={multiplication_digits.1} onchange={
+                                                let digits = multiplication_digits.clone();
+                                                Callback::from(move |y| digits.set((digits.0, y)))
+                                            }/>
+                                        </div>
+                                    </div>
+                                </li>
                                 <li class="w3-padding-16 w3-small"><b>{"100"}</b> {"примера"} </li>
                                 <li class="w3-light-grey w3-padding-16">
                                     <button onclick={add_multiplication} class="w3-button w3-teal w3-round w3-padding">{"Добави"}</button>
@@ -66,17 +259,298 @@ pub fn FreePlayCard() -> Html {
                             </ul>
                         </div>
 
+                        <div class="w3-half w3-margin-bottom">
+                            <ul class="w3-ul w3-border w3-center w3-hover-shadow">
+                                <li class="w3-large w3-padding-16 w3-text-grey"><i
+                                        class="fa fa-table-cells fa-fw w3-margin-right w3-xlarge w3-text-teal"></i>{"Таблица за Умножение"}</li>
+                                <li class="w3-padding-16 w3-small">
+                                    {tables_description(&tables)}
+                                    <div class="w3-bar w3-margin-top">
+                                        { for (2..=9).map(|table| {
+                                            let selected = tables.contains(&table);
+                                            let onclick = {
+                                                let tables = tables.clone();
+                                                Callback::from(move |_| {
+                                                    let mut next: Vec<i32> = tables.iter().copied().filter(|t| *t != table).collect();
+                                                    if !selected {
+                                                        next.push(table);
+                                                        next.sort();
+                                                    }
+                                                    tables.set(next);
+                                                })
+                                            };
+                                            html! {
+                                                <button {onclick} class={classes!("w3-bar-item", "w3-button", "w3-round", if selected { "w3-teal" } else { "w3-light-grey" })}>{table}</button>
+                                            }
+                                        })}
+                                    </div>
+                                </li>
+                                <li class="w3-padding-16 w3-small"><b>{"100"}</b> {"примера"} </li>
+                                <li class="w3-light-grey w3-padding-16">
+                                    <button onclick={add_tables} disabled={tables.is_empty()} class="w3-button w3-teal w3-round w3-padding">{"Добави"}</button>
+                                </li>
+                            </ul>
+                        </div>
+
                         <div class="w3-half w3-margin-bottom">
                             <ul class="w3-ul w3-border w3-center w3-hover-shadow">
                                 <li class="w3-large w3-padding-16 w3-text-grey"><i
                                         class="fa fa-plus-minus fa-fw w3-margin-right w3-xlarge w3-text-teal"></i>{"Събиране и Изваждане"}</li>
-                                <li class="w3-padding-16 w3-small">{"едноцифрено с едноцифрено"}</li>
+                                <li class="w3-padding-16 w3-small">
+                                    {addition_description(addition_digits.0, addition_digits.1, *addition_carry)}
+                                    <div class="w3-row-padding w3-margin-top">
+                                        <div class="w3-third">
+                                            <DigitsSelect value={addition_digits.0} onchange={
+                                                let digits = addition_digits.clone();
+                                                Callback::from(move |x| digits.set((x, digits.1)))
+                                            }/>
+                                        </div>
+                                        <div class="w3-third">
+                                            <DigitsSelect value={addition_digits.1} onchange={
+                                                let digits = addition_digits.clone();
+                                                Callback::from(move |y| digits.set((digits.0, y)))
+                                            }/>
+                                        </div>
+                                        <div class="w3-third">
+                                            <CarrySelect value={*addition_carry} onchange={
+                                                let carry = addition_carry.clone();
+                                                Callback::from(move |c| carry.set(c))
+                                            }/>
+                                        </div>
+                                    </div>
+                                </li>
                                 <li class="w3-padding-16 w3-small"><b>{"100"}</b> {"примера"} </li>
                                 <li class="w3-light-grey w3-padding-16">
                                     <button onclick={add_addition} class="w3-button w3-teal w3-round w3-padding">{"Добави"}</button>
                                 </li>
                             </ul>
                         </div>
+
+                        <div class="w3-half w3-margin-bottom">
+                            <ul class="w3-ul w3-border w3-center w3-hover-shadow">
+                                <li class="w3-large w3-padding-16 w3-text-grey"><i
+                                        class="fa fa-link fa-fw w3-margin-right w3-xlarge w3-text-teal"></i>{"Верижно Смятане"}</li>
+                                <li class="w3-padding-16 w3-small">
+                                    {chain_description(*chain_terms, *chain_digits)}
+                                    <div class="w3-row-padding w3-margin-top">
+                                        <div class="w3-half">
+                                            <CountSelect value={*chain_terms} range={3..=10} onchange={
+                                                let terms = chain_terms.clone();
+                                                Callback::from(move |t| terms.set(t))
+                                            }/>
+                                        </div>
+                                        <div class="w3-half">
+                                            <DigitsSelect value={*chain_digits} onchange={
+                                                let digits = chain_digits.clone();
+                                                Callback::from(move |d| digits.set(d))
+                                            }/>
+                                        </div>
+                                    </div>
+                                </li>
+                                <li class="w3-padding-16 w3-small"><b>{"100"}</b> {"примера"} </li>
+                                <li class="w3-light-grey w3-padding-16">
+                                    <button onclick={add_chain} class="w3-button w3-teal w3-round w3-padding">{"Добави"}</button>
+                                </li>
+                            </ul>
+                        </div>
+
+                        <div class="w3-half w3-margin-bottom">
+                            <ul class="w3-ul w3-border w3-center w3-hover-shadow">
+                                <li class="w3-large w3-padding-16 w3-text-grey"><i
+                                        class="fa fa-bolt fa-fw w3-margin-right w3-xlarge w3-text-teal"></i>{"Флаш Анзан"}</li>
+                                <li class="w3-padding-16 w3-small">
+                                    {flash_anzan_description(*flash_count, *flash_digits, *flash_interval)}
+                                    <div class="w3-row-padding w3-margin-top">
+                                        <div class="w3-third">
+                                            <CountSelect value={*flash_count} range={3..=15} onchange={
+                                                let count = flash_count.clone();
+                                                Callback::from(move |c| count.set(c))
+                                            }/>
+                                        </div>
+                                        <div class="w3-third">
+                                            <DigitsSelect value={*flash_digits} onchange={
+                                                let digits = flash_digits.clone();
+                                                Callback::from(move |d| digits.set(d))
+                                            }/>
+                                        </div>
+                                        <div class="w3-third">
+                                            <IntervalSelect value={*flash_interval} onchange={
+                                                let interval = flash_interval.clone();
+                                                Callback::from(move |i| interval.set(i))
+                                            }/>
+                                        </div>
+                                    </div>
+                                </li>
+                                <li class="w3-padding-16 w3-small"><b>{"10"}</b> {"примера"} </li>
+                                <li class="w3-light-grey w3-padding-16">
+                                    <button onclick={add_flash_anzan} class="w3-button w3-teal w3-round w3-padding">{"Добави"}</button>
+                                </li>
+                            </ul>
+                        </div>
+
+                        <div class="w3-half w3-margin-bottom">
+                            <ul class="w3-ul w3-border w3-center w3-hover-shadow">
+                                <li class="w3-large w3-padding-16 w3-text-grey"><i
+                                        class="fa fa-handshake fa-fw w3-margin-right w3-xlarge w3-text-teal"></i>{complements_title(*complements_target)}</li>
+                                <li class="w3-padding-16 w3-small">
+                                    {format!("допълване до {}", *complements_target)}
+                                    <div class="w3-row-padding w3-margin-top">
+                                        <TargetSelect value={*complements_target} onchange={
+                                            let target = complements_target.clone();
+                                            Callback::from(move |t| target.set(t))
+                                        }/>
+                                    </div>
+                                </li>
+                                <li class="w3-padding-16 w3-small"><b>{"100"}</b> {"примера"} </li>
+                                <li class="w3-light-grey w3-padding-16">
+                                    <button onclick={add_complements} class="w3-button w3-teal w3-round w3-padding">{"Добави"}</button>
+                                </li>
+                            </ul>
+                        </div>
+
+                        <div class="w3-half w3-margin-bottom">
+                            <ul class="w3-ul w3-border w3-center w3-hover-shadow">
+                                <li class="w3-large w3-padding-16 w3-text-grey"><i
+                                        class="fa fa-question fa-fw w3-margin-right w3-xlarge w3-text-teal"></i>{"Намери Липсващото"}</li>
+                                <li class="w3-padding-16 w3-small">
+                                    {unknowns_description(&missing_unknowns[*missing_unknown].1)}
+                                    <div class="w3-row-padding w3-margin-top">
+                                        <div class="w3-half">
+                                            <IndexSelect
+                                                options={missing_modes.iter().map(|(name, _)| AttrValue::from(*name)).collect::<Vec<_>>()}
+                                                value={*missing_mode}
+                                                onchange={
+                                                    let mode = missing_mode.clone();
+                                                    Callback::from(move |m| mode.set(m))
+                                                }/>
+                                        </div>
+                                        <div class="w3-half">
+                                            <IndexSelect
+                                                options={missing_unknowns.iter().map(|(name, _)| AttrValue::from(*name)).collect::<Vec<_>>()}
+                                                value={*missing_unknown}
+                                                onchange={
+                                                    let unknown = missing_unknown.clone();
+                                                    Callback::from(move |u| unknown.set(u))
+                                                }/>
+                                        </div>
+                                    </div>
+                                </li>
+                                <li class="w3-padding-16 w3-small"><b>{"100"}</b> {"примера"} </li>
+                                <li class="w3-light-grey w3-padding-16">
+                                    <button onclick={add_missing} class="w3-button w3-teal w3-round w3-padding">{"Добави"}</button>
+                                </li>
+                            </ul>
+                        </div>
+
+                        <div class="w3-half w3-margin-bottom">
+                            <ul class="w3-ul w3-border w3-center w3-hover-shadow">
+                                <li class="w3-large w3-padding-16 w3-text-grey"><i
+                                        class="fa fa-ellipsis-vertical fa-fw w3-margin-right w3-xlarge w3-text-teal"></i>{"Четене от Соробан"}</li>
+                                <li class="w3-padding-16 w3-small">
+                                    {format!("до {} числа", digits_plural(*reading_digits))}
+                                    <div class="w3-row-padding w3-margin-top">
+                                        <DigitsSelect value={*reading_digits} onchange={
+                                            let digits = reading_digits.clone();
+                                            Callback::from(move |d| digits.set(d))
+                                        }/>
+                                    </div>
+                                </li>
+                                <li class="w3-padding-16 w3-small"><b>{"100"}</b> {"примера"} </li>
+                                <li class="w3-light-grey w3-padding-16">
+                                    <button onclick={add_reading} class="w3-button w3-teal w3-round w3-padding">{"Добави"}</button>
+                                </li>
+                            </ul>
+                        </div>
+
+                        <div class="w3-half w3-margin-bottom">
+                            <ul class="w3-ul w3-border w3-center w3-hover-shadow">
+                                <li class="w3-large w3-padding-16 w3-text-grey"><i
+                                        class="fa fa-shuffle fa-fw w3-margin-right w3-xlarge w3-text-teal"></i>{"Смесено"}</li>
+                                <li class="w3-padding-16 w3-small">
+                                    {mix_description(&[(mix_weights.0, "умножение"), (mix_weights.1, "събиране и изваждане"), (mix_weights.2, "деление")])}
+                                    <div class="w3-row-padding w3-margin-top">
+                                        <div class="w3-third">
+                                            <WeightSelect label="x" value={mix_weights.0} onchange={
+                                                let weights = mix_weights.clone();
+                                                Callback::from(move |w| weights.set((w, weights.1, weights.2)))
+                                            }/>
+                                        </div>
+                                        <div class="w3-third">
+                                            <WeightSelect label="+/-" value={mix_weights.1} onchange={
+                                                let weights = mix_weights.clone();
+                                                Callback::from(move |w| weights.set((weights.0, w, weights.2)))
+                                            }/>
+                                        </div>
+                                        <div class="w3-third">
+                                            <WeightSelect label=":" value={mix_weights.2} onchange={
+                                                let weights = mix_weights.clone();
+                                                Callback::from(move |w| weights.set((weights.0, weights.1, w)))
+                                            }/>
+                                        </div>
+                                    </div>
+                                </li>
+                                <li class="w3-padding-16 w3-small"><b>{"100"}</b> {"примера"} </li>
+                                <li class="w3-light-grey w3-padding-16">
+                                    <button onclick={add_mix} disabled={mix_weights.0 + mix_weights.1 + mix_weights.2 == 0} class="w3-button w3-teal w3-round w3-padding">{"Добави"}</button>
+                                </li>
+                            </ul>
+                        </div>
+
+                        <div class="w3-half w3-margin-bottom">
+                            <ul class="w3-ul w3-border w3-center w3-hover-shadow">
+                                <li class="w3-large w3-padding-16 w3-text-grey"><i
+                                        class="fa fa-layer-group fa-fw w3-margin-right w3-xlarge w3-text-teal"></i>{"Смесено Домашно"}</li>
+                                <li class="w3-padding-16 w3-small">{"умножение, после събиране, после деление"}</li>
+                                <li class="w3-padding-16 w3-small"><b>{"20 + 20 + 10"}</b> {"примера"} </li>
+                                <li class="w3-light-grey w3-padding-16">
+                                    <button onclick={add_composite} class="w3-button w3-teal w3-round w3-padding">{"Добави"}</button>
+                                </li>
+                            </ul>
+                        </div>
+
+                        <div class="w3-half w3-margin-bottom">
+                            <ul class="w3-ul w3-border w3-center w3-hover-shadow">
+                                <li class="w3-large w3-padding-16 w3-text-grey"><i
+                                        class="fa fa-stopwatch fa-fw w3-margin-right w3-xlarge w3-text-teal"></i>{"Спринт"}</li>
+                                <li class="w3-padding-16 w3-small">
+                                    {sprint_description(*sprint_seconds)}
+                                    <div class="w3-row-padding w3-margin-top">
+                                        <SecondsSelect value={*sprint_seconds} onchange={
+                                            let seconds = sprint_seconds.clone();
+                                            Callback::from(move |s| seconds.set(s))
+                                        }/>
+                                    </div>
+                                </li>
+                                <li class="w3-padding-16 w3-small">{"умножение в таблицата"}</li>
+                                <li class="w3-light-grey w3-padding-16">
+                                    <button onclick={add_sprint} class="w3-button w3-teal w3-round w3-padding">{"Добави"}</button>
+                                </li>
+                            </ul>
+                        </div>
+
+                        <div class="w3-half w3-margin-bottom">
+                            <ul class="w3-ul w3-border w3-center w3-hover-shadow">
+                                <li class="w3-large w3-padding-16 w3-text-grey"><i
+                                        class="fa fa-divide fa-fw w3-margin-right w3-xlarge w3-text-teal"></i>{"Деление"}</li>
+                                <li class="w3-padding-16 w3-small">{"в таблицата за умножение"}</li>
+                                <li class="w3-padding-16 w3-small"><b>{"100"}</b> {"примера"} </li>
+                                <li class="w3-light-grey w3-padding-16">
+                                    <button onclick={add_division} class="w3-button w3-teal w3-round w3-padding">{"Добави"}</button>
+                                </li>
+                            </ul>
+                        </div>
+
+                        <div class="w3-half w3-margin-bottom">
+                            <ul class="w3-ul w3-border w3-center w3-hover-shadow">
+                                <li class="w3-large w3-padding-16 w3-text-grey"><i
+                                        class="fa fa-divide fa-fw w3-margin-right w3-xlarge w3-text-teal"></i>{"Деление с остатък"}</li>
+                                <li class="w3-padding-16 w3-small">{"с едноцифрено частно и делител"}</li>
+                                <li class="w3-padding-16 w3-small"><b>{"100"}</b> {"примера"} </li>
+                                <li class="w3-light-grey w3-padding-16">
+                                    <button onclick={add_remainder} class="w3-button w3-teal w3-round w3-padding">{"Добави"}</button>
+                                </li>
+                            </ul>
+                        </div>
                     </div>
                 </div>
                 <footer class="w3-container w3-teal">
@@ -103,3 +577,225 @@ pub fn FreePlayCard() -> Html {
         </>
     }
 }
+
+// A callback adding the assignment made by `make`, or showing why its
+// settings were rejected.
+fn add_assignment<F>(
+    dispatch: &Dispatch<Assignments>,
+    error: &UseStateHandle<Option<ConfigError>>,
+    make: F,
+) -> Callback<MouseEvent>
+where
+    F: Fn() -> Result<Assignment, ConfigError> + 'static,
+{
+    let dispatch = dispatch.clone();
+    let error = error.clone();
+    Callback::from(move |_| match make() {
+        Ok(assignment) => {
+            dispatch.reduce_mut(|assignments| assignments.push(assignment));
+            error.set(None);
+        }
+        Err(err) => error.set(Some(err)),
+    })
+}
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct DigitsSelectProps {
+    pub value: u32,
+    pub onchange: Callback<u32>,
+}
+
+#[function_component]
+pub(crate) fn DigitsSelect(DigitsSelectProps { value, onchange }: &DigitsSelectProps) -> Html {
+    let onchange = onchange.reform(|e: Event| {
+        let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+        select.value().parse().unwrap_or(1)
+    });
+    html! {
+        <select class="w3-select" {onchange}>
+            { for (1..=4).map(|digits| html! {
+                <option value={digits.to_string()} selected={digits == *value}>{digits_name(digits)}</option>
+            })}
+        </select>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct CountSelectProps {
+    value: u32,
+    range: RangeInclusive<u32>,
+    onchange: Callback<u32>,
+}
+
+#[function_component]
+fn CountSelect(
+    CountSelectProps {
+        value,
+        range,
+        onchange,
+    }: &CountSelectProps,
+) -> Html {
+    let default = *range.start();
+    let onchange = onchange.reform(move |e: Event| {
+        let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+        select.value().parse().unwrap_or(default)
+    });
+    html! {
+        <select class="w3-select" {onchange}>
+            { for range.clone().map(|count| html! {
+                <option value={count.to_string()} selected={count == *value}>{count}</option>
+            })}
+        </select>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct IntervalSelectProps {
+    value: u32,
+    onchange: Callback<u32>,
+}
+
+#[function_component]
+fn IntervalSelect(IntervalSelectProps { value, onchange }: &IntervalSelectProps) -> Html {
+    let onchange = onchange.reform(|e: Event| {
+        let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+        select.value().parse().unwrap_or(1000)
+    });
+    html! {
+        <select class="w3-select" {onchange}>
+            { for [250, 500, 750, 1000, 1500, 2000].into_iter().map(|interval: u32| html! {
+                <option value={interval.to_string()} selected={interval == *value}>
+                    {format!("{:.2} сек.", interval as f32 / 1000_f32)}
+                </option>
+            })}
+        </select>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct WeightSelectProps {
+    label: AttrValue,
+    value: u32,
+    onchange: Callback<u32>,
+}
+
+#[function_component]
+fn WeightSelect(
+    WeightSelectProps {
+        label,
+        value,
+        onchange,
+    }: &WeightSelectProps,
+) -> Html {
+    let onchange = onchange.reform(|e: Event| {
+        let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+        select.value().parse().unwrap_or(0)
+    });
+    html! {
+        <select class="w3-select" {onchange}>
+            { for (0..=100).step_by(10).map(|weight: u32| html! {
+                <option value={weight.to_string()} selected={weight == *value}>{format!("{} {}%", label, weight)}</option>
+            })}
+        </select>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct SecondsSelectProps {
+    value: u32,
+    onchange: Callback<u32>,
+}
+
+#[function_component]
+fn SecondsSelect(SecondsSelectProps { value, onchange }: &SecondsSelectProps) -> Html {
+    let onchange = onchange.reform(|e: Event| {
+        let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+        select.value().parse().unwrap_or(60)
+    });
+    html! {
+        <select class="w3-select" {onchange}>
+            { for [30, 60, 120, 180, 300].into_iter().map(|seconds: u32| html! {
+                <option value={seconds.to_string()} selected={seconds == *value}>{format!("{} сек.", seconds)}</option>
+            })}
+        </select>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct TargetSelectProps {
+    value: i32,
+    onchange: Callback<i32>,
+}
+
+#[function_component]
+fn TargetSelect(TargetSelectProps { value, onchange }: &TargetSelectProps) -> Html {
+    let onchange = onchange.reform(|e: Event| {
+        let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+        select.value().parse().unwrap_or(10)
+    });
+    html! {
+        <select class="w3-select" {onchange}>
+            { for [5, 10, 100].into_iter().map(|target: i32| html! {
+                <option value={target.to_string()} selected={target == *value}>{format!("до {}", target)}</option>
+            })}
+        </select>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct IndexSelectProps {
+    options: Vec<AttrValue>,
+    value: usize,
+    onchange: Callback<usize>,
+}
+
+// A select over a list of labels that reports the index of the chosen one.
+#[function_component]
+fn IndexSelect(
+    IndexSelectProps {
+        options,
+        value,
+        onchange,
+    }: &IndexSelectProps,
+) -> Html {
+    let onchange = onchange.reform(|e: Event| {
+        let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+        select.selected_index().max(0) as usize
+    });
+    html! {
+        <select class="w3-select" {onchange}>
+            { for options.iter().enumerate().map(|(index, label)| html! {
+                <option selected={index == *value}>{label.clone()}</option>
+            })}
+        </select>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct CarrySelectProps {
+    value: Carry,
+    onchange: Callback<Carry>,
+}
+
+#[function_component]
+fn CarrySelect(CarrySelectProps { value, onchange }: &CarrySelectProps) -> Html {
+    let options = [
+        (Carry::Any, "any", "с и без преминаване"),
+        (Carry::Required, "required", "с преминаване"),
+        (Carry::Forbidden, "forbidden", "без преминаване"),
+    ];
+    let onchange = onchange.reform(move |e: Event| {
+        let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+        options
+            .iter()
+            .find(|(_, key, _)| *key == select.value())
+            .map_or(Carry::Any, |(carry, _, _)| *carry)
+    });
+    html! {
+        <select class="w3-select" {onchange}>
+            { for options.iter().map(|(carry, key, name)| html! {
+                <option value={*key} selected={carry == value}>{*name}</option>
+            })}
+        </select>
+    }
+}
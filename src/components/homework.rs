@@ -1,24 +1,209 @@
 use std::cmp::Ordering;
 
 use chrono::format::{DelayedFormat, StrftimeItems};
-use chrono::{Locale, Utc};
+use chrono::{Locale, NaiveDate, Utc};
 use yew::prelude::*;
 use yewdux::prelude::*;
 
-use crate::{components::assignment::AssignmentList, model::ui::Homework};
+use crate::{
+    components::assignment::AssignmentList,
+    model::{
+        schedule::{Schedule, Template, WEEKDAYS},
+        ui::{Assignments, Homework, Presets},
+    },
+};
+
+// The limits on backfilled days offered by the schedule editor.
+const BACKFILLS: [(Option<u32>, &str); 5] = [
+    (None, "Наваксване на всички пропуснати дни"),
+    (Some(0), "Без наваксване"),
+    (Some(1), "Наваксване на 1 пропуснат ден"),
+    (Some(3), "Наваксване на 3 пропуснати дни"),
+    (Some(7), "Наваксване на 7 пропуснати дни"),
+];
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
 
 #[function_component]
 pub fn HomeworkCard() -> Html {
+    let show_modal = use_state(|| false);
+    let open = {
+        let show_modal = show_modal.clone();
+        Callback::from(move |_| show_modal.set(true))
+    };
+    let close = {
+        let show_modal = show_modal.clone();
+        Callback::from(move |_| show_modal.set(false))
+    };
+    let modal_style = if *show_modal {
+        "display: block;"
+    } else {
+        "display: none;"
+    };
     html! {
-        <div class="w3-container w3-card w3-white w3-margin-bottom w3-padding">
+        <>
+        // <!-- Modal window for editing the homework schedule -->
+        <div class="w3-modal" style={modal_style}>
+            <div class="w3-modal-content">
+                <header class="w3-container w3-teal">
+                    <h4>{"Разписание"}</h4>
+                </header>
+                <div class="w3-container w3-padding-16">
+                    <ScheduleEditor/>
+                </div>
+                <footer class="w3-container w3-teal">
+                    <div class="w3-bar">
+                        <button onclick={close}
+                            class="w3-bar-item w3-right w3-button w3-round w3-green w3-margin">{"Готово"}</button>
+                    </div>
+                </footer>
+            </div>
+        </div>
+        <div class="w3-container w3-card w3-white w3-margin-bottom w3-padding w3-display-container">
+            <div class="w3-container w3-display-topright w3-margin">
+                <button onclick={open} class="w3-button w3-teal w3-round"><i class="fa fa-solid fa-calendar-days"></i></button>
+            </div>
             <h2 class="w3-text-grey w3-padding-16">
                 <i class="fa fa-suitcase fa-fw w3-margin-right w3-xxlarge w3-text-teal"></i>{"Домашна Работа"}
             </h2>
             <HomeworkList/>
         </div>
+        </>
     }
 }
 
+// Edits the schedule the homework is given by. Every change is saved right
+// away and shows up the next time the homework is filled in.
+#[function_component]
+fn ScheduleEditor() -> Html {
+    let (store, dispatch) = use_store::<Assignments>();
+    let (presets, _) = use_store::<Presets>();
+    let schedule = store.schedule.clone();
+    let set_schedule = dispatch
+        .reduce_mut_callback_with(|assignments, schedule| assignments.set_schedule(schedule));
+    let change = |f: fn(&mut Schedule, String)| {
+        let schedule = schedule.clone();
+        set_schedule.reform(move |e: Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            let mut next = schedule.clone();
+            f(&mut next, input.value());
+            next
+        })
+    };
+    let templates: Vec<Template> = [
+        Template::Review,
+        Template::Multiplication,
+        Template::Division,
+        Template::Addition,
+        Template::Sprint,
+    ]
+    .into_iter()
+    .chain(
+        presets
+            .presets
+            .iter()
+            .map(|preset| Template::Preset(preset.name.clone())),
+    )
+    .collect();
+    let select_backfill = {
+        let schedule = schedule.clone();
+        set_schedule.reform(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            let mut next = schedule.clone();
+            if let Some((max, _)) = BACKFILLS.get(select.selected_index().max(0) as usize) {
+                next.max_backfill = *max;
+            }
+            next
+        })
+    };
+    let date_value = |day: Option<NaiveDate>| {
+        day.map_or_else(String::new, |day| day.format(DATE_FORMAT).to_string())
+    };
+
+    html! {
+        <>
+        <table class="w3-table w3-bordered">
+            { for WEEKDAYS.iter().enumerate().map(|(weekday, name)| html! {
+                <tr>
+                    <td class="w3-text-teal">{*name}</td>
+                    <td>
+                        { for templates.iter().map(|template| {
+                            let selected = schedule.weekdays[weekday].contains(template);
+                            let onclick = {
+                                let schedule = schedule.clone();
+                                let template = template.clone();
+                                set_schedule.reform(move |_| {
+                                    let mut next = schedule.clone();
+                                    let day = &mut next.weekdays[weekday];
+                                    day.retain(|t| *t != template);
+                                    if !selected {
+                                        day.push(template.clone());
+                                    }
+                                    next
+                                })
+                            };
+                            html! {
+                                <button {onclick} class={classes!("w3-button", "w3-round", "w3-small", "w3-margin-right", if selected { "w3-teal" } else { "w3-light-grey" })}>{template.name()}</button>
+                            }
+                        })}
+                    </td>
+                </tr>
+            })}
+        </table>
+        <div class="w3-row-padding w3-margin-top">
+            <div class="w3-half">
+                <label>{"Начало"}</label>
+                <input class="w3-input" type="date" value={date_value(schedule.start)}
+                    onchange={change(|s, value| s.start = parse_date(&value))}/>
+            </div>
+            <div class="w3-half">
+                <label>{"Край"}</label>
+                <input class="w3-input" type="date" value={date_value(schedule.end)}
+                    onchange={change(|s, value| s.end = parse_date(&value))}/>
+            </div>
+        </div>
+        <div class="w3-row-padding w3-margin-top">
+            <div class="w3-half">
+                <label>{"Почивен ден"}</label>
+                <input class="w3-input" type="date" value=""
+                    onchange={change(|s, value| s.days_off.extend(parse_date(&value)))}/>
+            </div>
+            <div class="w3-half">
+                <label>{"Пропуснати дни"}</label>
+                <select class="w3-select" onchange={select_backfill}>
+                    { for BACKFILLS.iter().map(|(max, label)| html! {
+                        <option selected={*max == schedule.max_backfill}>{*label}</option>
+                    })}
+                </select>
+            </div>
+        </div>
+        <div class="w3-container w3-margin-top">
+            { for schedule.days_off.iter().map(|day| {
+                let onclick = {
+                    let schedule = schedule.clone();
+                    let day = *day;
+                    set_schedule.reform(move |_| {
+                        let mut next = schedule.clone();
+                        next.days_off.remove(&day);
+                        next
+                    })
+                };
+                html! {
+                    <span class="w3-tag w3-round w3-light-grey w3-margin-right w3-margin-bottom">
+                        {DelayedFormat::new_with_locale(Some(*day), None, StrftimeItems::new("%d %B %Y"), Locale::bg_BG)}
+                        <button {onclick} class="w3-button w3-small w3-padding-small">{"×"}</button>
+                    </span>
+                }
+            })}
+        </div>
+        </>
+    }
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, DATE_FORMAT).ok()
+}
+
 #[function_component]
 fn HomeworkList() -> Html {
     let (state, _dispatch) = use_store::<Homework>();
@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use yew::{
+    platform::{spawn_local, time::sleep},
+    prelude::*,
+};
+
+#[derive(Properties, PartialEq)]
+pub struct SorobanProps {
+    pub value: i32,
+    /// The minimum number of rods, more are added if the value needs them.
+    #[prop_or(3)]
+    pub rods: usize,
+    /// When set, the beads start from this value and move to `value`.
+    #[prop_or_default]
+    pub from: Option<i32>,
+    #[prop_or_default]
+    pub small: bool,
+}
+
+/// Renders a number as the bead positions of a soroban. Changing the value
+/// slides the beads to their new positions.
+#[function_component]
+pub fn Soroban(
+    SorobanProps {
+        value,
+        rods,
+        from,
+        small,
+    }: &SorobanProps,
+) -> Html {
+    let shown = use_state(|| from.unwrap_or(*value));
+    {
+        let shown = shown.clone();
+        use_effect_with_deps(
+            move |value| {
+                let value = *value;
+                if *shown != value {
+                    // Let the starting position render first, so the beads slide.
+                    spawn_local(async move {
+                        sleep(Duration::from_millis(100)).await;
+                        shown.set(value);
+                    });
+                }
+            },
+            *value,
+        );
+    }
+
+    let rods = (*rods)
+        .max(rod_digits(*value).len())
+        .max(from.map_or(0, |from| rod_digits(from).len()));
+    let mut digits = rod_digits(*shown);
+    digits.resize(rods, 0);
+
+    html! {
+        <div class={classes!("soroban", small.then_some("soroban-small"))}>
+            { for digits.into_iter().rev().map(|digit| html! {
+                <div class="soroban-rod">
+                    <div class="soroban-heaven">
+                        <div class={classes!("soroban-bead", (digit >= 5).then_some("soroban-bead-active"))}></div>
+                    </div>
+                    <div class="soroban-earth">
+                        { for (0..4).map(|bead| html! {
+                            <div class={classes!("soroban-bead", (bead < digit % 5).then_some("soroban-bead-active"))}></div>
+                        })}
+                    </div>
+                </div>
+            })}
+        </div>
+    }
+}
+
+// The digits of the value from the units rod up. The soroban has no sign, so
+// negative values are shown by their magnitude.
+fn rod_digits(value: i32) -> Vec<i32> {
+    let mut value = value.abs();
+    let mut digits = vec![value % 10];
+    while value >= 10 {
+        value /= 10;
+        digits.push(value % 10);
+    }
+    digits
+}
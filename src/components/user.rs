@@ -1,7 +1,10 @@
 use yew::prelude::*;
 use yewdux::prelude::use_store;
 
-use crate::model::{task::TaskBuilderMode, ui::Assignments};
+use crate::model::{
+    task::{Operation, Prompt, Task, TaskState},
+    ui::Assignments,
+};
 
 #[function_component]
 pub fn UserCard() -> Html {
@@ -35,43 +38,30 @@ fn UserAvatar() -> Html {
 fn UserSkills() -> Html {
     let (store, _d) = use_store::<Assignments>();
 
-    let mut multiplication_count = 0;
-    let mut multiplication_score = 0_f32;
-
-    let mut addition_count = 0;
-    let mut addition_score = 0_f32;
+    let mut multiplication = Skill::default();
+    let mut division = Skill::default();
+    let mut addition = Skill::default();
+    let mut reading = Skill::default();
+    // Each task counts towards the skill of its own operation, so mixed
+    // assignments and reviews are scored too.
     store
         .assignments
         .iter()
         .filter(|a| a.is_done())
-        .for_each(|a| {
-            let progress = a.progress();
-            match a.builder.mode {
-                TaskBuilderMode::Multiplication => {
-                    multiplication_score +=
-                        progress.correct as f32 / (progress.correct + progress.wrong) as f32;
-                    multiplication_count += 1;
-                }
-                TaskBuilderMode::AdditionAndSubtraction => {
-                    addition_score +=
-                        progress.correct as f32 / (progress.correct + progress.wrong) as f32;
-                    addition_count += 1;
-                }
-            }
+        .flat_map(|a| a.tasks.iter())
+        .for_each(|task| {
+            let skill = match (task.prompt, task.op) {
+                (Prompt::Soroban, _) => &mut reading,
+                (_, Operation::Multiplication) => &mut multiplication,
+                (_, Operation::Division | Operation::DivisionWithRemainder) => &mut division,
+                (_, Operation::Addition | Operation::Subtraction) => &mut addition,
+            };
+            skill.record(task);
         });
-    let multiplication_score = if multiplication_count == 0 {
-        "N/A".to_owned()
-    } else {
-        format!(
-            "{:.0}%",
-            (multiplication_score / multiplication_count as f32) * 100_f32
-        )
-    };
-    let addition_score = if addition_count == 0 {
-        "N/A".to_owned()
-    } else {
-        format!("{:.0}%", (addition_score / addition_count as f32) * 100_f32)
-    };
+    let multiplication_score = multiplication.score();
+    let division_score = division.score();
+    let addition_score = addition.score();
+    let reading_score = reading.score();
 
     html! {
         <>
@@ -94,7 +84,7 @@ fn UserSkills() -> Html {
                 <div class="w3-col s8"><i class="fa fa-solid fa-divide w3-small w3-text-teal w3-margin-right"></i>{"Деление"}</div>
                 <div class="w3-col s4">
                     <div class="w3-light-grey w3-round-xlarge w3-small">
-                        <div class="w3-container w3-center w3-round-xlarge w3-teal" style="width: 100%">{"N/A"}</div>
+                        <div class="w3-container w3-center w3-round-xlarge w3-teal" style="width: 100%">{division_score}</div>
                     </div>
                 </div>
             </div>
@@ -109,11 +99,53 @@ fn UserSkills() -> Html {
                     </div>
                 </div>
             </div>
+
+            <div class="w3-row w3-margin-left w3-margin-top">
+                <div class="w3-col s8">
+                    <i class="fa fa-solid fa-ellipsis-vertical w3-small w3-text-teal w3-margin-right"></i> {"Четене от Соробан"}
+                </div>
+                <div class="w3-col s4">
+                    <div class="w3-light-grey w3-round-xlarge w3-small">
+                        <div class="w3-container w3-center w3-round-xlarge w3-teal" style="width: 100%">{reading_score}</div>
+                    </div>
+                </div>
+            </div>
             <hr />
         </>
     }
 }
 
+#[derive(Default)]
+struct Skill {
+    correct: u32,
+    attempted: u32,
+}
+
+impl Skill {
+    // Running out of time counts against the skill, skipping does not.
+    fn record(&mut self, task: &Task) {
+        match task.state() {
+            TaskState::Correct | TaskState::CorrectAfter(_) => {
+                self.correct += 1;
+                self.attempted += 1;
+            }
+            TaskState::Wrong | TaskState::TimedOut => self.attempted += 1,
+            TaskState::Skipped => {}
+        }
+    }
+
+    fn score(&self) -> String {
+        if self.attempted == 0 {
+            "N/A".to_owned()
+        } else {
+            format!(
+                "{:.0}%",
+                self.correct as f32 / self.attempted as f32 * 100_f32
+            )
+        }
+    }
+}
+
 #[function_component]
 fn UserAchievements() -> Html {
     html! {
@@ -1,3 +1,10 @@
 pub mod assignment;
+pub mod config;
+pub mod criteria;
+pub mod fact;
+pub mod memory;
+pub mod operand;
+pub mod preset;
+pub mod schedule;
 pub mod task;
 pub mod ui;
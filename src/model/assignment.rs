@@ -1,57 +1,498 @@
-use chrono::NaiveDate;
-use rand::distributions::Uniform;
+use chrono::{NaiveDate, Utc};
 use serde_derive::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use super::task::{Task, TaskBuilder, TaskBuilderMode};
+use super::{
+    config::ConfigError,
+    criteria::{Criteria, Criterion, OnFailure},
+    fact::{Fact, History},
+    operand::{Carry, OperandRange},
+    task::{
+        Answer, Attempt, MixPart, Sampling, Task, TaskBuilder, TaskBuilderMode, TaskState,
+        UnknownWeights,
+    },
+};
+
+/// The most facts a review goes over, the ones due the longest first.
+pub const MAX_REVIEW_FACTS: usize = 50;
 
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
 pub struct Assignment {
     pub id: Uuid,
     pub due_date: Option<NaiveDate>,
     pub timed: bool,
+    /// The time budget of a sprint in seconds. A sprint ends when the time
+    /// runs out, and has no task limit when `num_tasks` is 0.
+    #[serde(default)]
+    pub time_limit: Option<u32>,
+    /// Seconds to answer each task in. A task that runs out of time is
+    /// submitted unanswered and the next one is shown.
+    #[serde(default)]
+    pub task_limit: Option<u32>,
+    /// How many tasks the child may skip, `None` for no limit.
+    #[serde(default)]
+    pub max_skips: Option<u32>,
+    /// Skipped tasks come back at the end of the assignment.
+    #[serde(default)]
+    pub requeue_skipped: bool,
+    /// A wrong answer keeps the task until it is answered correctly.
+    #[serde(default)]
+    pub retry: bool,
+    #[serde(default)]
+    pub criteria: Criteria,
+    /// Show the operands of the tasks on a soroban instead of as digits.
+    #[serde(default)]
+    pub abacus: bool,
+    /// Seeds the generation of the tasks, so the same assignment always
+    /// gives the same tasks in the same order.
+    #[serde(default = "rand::random")]
+    pub seed: u64,
     pub title: String,
     pub description: String,
     pub num_tasks: i32,
     pub tasks: Vec<Task>,
+    /// The task waiting for an answer. It is kept with the assignment, so
+    /// that re-renders and reloads show the same task without restarting
+    /// its clock.
+    #[serde(default)]
+    pub pending: Option<Task>,
+    /// Skipped tasks waiting to be asked again at the end.
+    #[serde(default)]
+    pub requeued: Vec<Task>,
+    /// The index of the first task of the current round. Tasks before it
+    /// belong to rounds that were restarted for missing the criteria.
+    #[serde(default)]
+    pub round_start: usize,
+    /// The parts of a composite assignment, done one after the other. When
+    /// there are sections, they build the tasks instead of `builder`.
+    #[serde(default)]
+    pub sections: Vec<Section>,
+    pub builder: TaskBuilder,
+}
+
+/// A part of a composite assignment with its own kind of tasks.
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub struct Section {
+    pub title: String,
+    pub num_tasks: i32,
     pub builder: TaskBuilder,
+    /// Seconds to answer each task of the section in, instead of the
+    /// assignment's limit.
+    #[serde(default)]
+    pub task_limit: Option<u32>,
+}
+
+impl Section {
+    /// A section of `num_tasks` of the builder's tasks, without a time
+    /// limit of its own.
+    pub fn new(title: &str, builder: TaskBuilder, num_tasks: i32) -> Self {
+        Self {
+            title: title.to_owned(),
+            num_tasks,
+            builder,
+            task_limit: None,
+        }
+    }
 }
 
 impl Assignment {
-    pub fn new_sd_sd_multiplication(num_tasks: i32, due_date: Option<NaiveDate>) -> Self {
-        let builder = TaskBuilder {
-            mode: TaskBuilderMode::Multiplication,
-            xrange: Uniform::new(2, 10),
-            yrange: Uniform::new(2, 10),
-        };
+    // An assignment of the builder's tasks with the default settings, which
+    // the constructors change as their kind of assignment needs.
+    fn with_builder(
+        title: String,
+        description: String,
+        builder: TaskBuilder,
+        num_tasks: i32,
+        due_date: Option<NaiveDate>,
+    ) -> Self {
         Self {
             id: Uuid::new_v4(),
             due_date,
             timed: true,
-            title: "Умножение".to_owned(),
-            description: "едноцифрено по едноцифрено".to_owned(),
+            time_limit: None,
+            task_limit: None,
+            max_skips: None,
+            requeue_skipped: false,
+            retry: false,
+            criteria: Criteria::default(),
+            abacus: false,
+            seed: rand::random(),
+            title,
+            description,
             num_tasks,
             tasks: Vec::new(),
-            builder: builder,
+            pending: None,
+            requeued: Vec::new(),
+            round_start: 0,
+            sections: Vec::new(),
+            builder,
         }
     }
 
-    pub fn new_sd_sd_addition(num_tasks: i32, due_date: Option<NaiveDate>) -> Self {
+    pub fn new_sd_sd_multiplication(
+        num_tasks: i32,
+        due_date: Option<NaiveDate>,
+    ) -> Result<Self, ConfigError> {
+        Self::new_multiplication(1, 1, num_tasks, due_date)
+    }
+
+    /// Multiplication of an `xdigits` number by an `ydigits` number. Single
+    /// digit factors start from 2, since multiplying by 1 teaches nothing.
+    pub fn new_multiplication(
+        xdigits: u32,
+        ydigits: u32,
+        num_tasks: i32,
+        due_date: Option<NaiveDate>,
+    ) -> Result<Self, ConfigError> {
+        let builder = multiplication_builder(xdigits, ydigits)?;
+        Ok(Self::with_builder(
+            "Умножение".to_owned(),
+            multiplication_description(xdigits, ydigits),
+            builder,
+            num_tasks,
+            due_date,
+        ))
+    }
+
+    /// An assignment of the given builder's tasks, e.g. one made in the
+    /// designer.
+    pub fn new_custom(
+        title: String,
+        description: String,
+        builder: TaskBuilder,
+        num_tasks: i32,
+        due_date: Option<NaiveDate>,
+    ) -> Result<Self, ConfigError> {
+        let builder = builder.validated()?;
+        Ok(Self::with_builder(
+            title,
+            description,
+            builder,
+            num_tasks,
+            due_date,
+        ))
+    }
+
+    /// A sprint of single digit multiplication: as many correct answers as
+    /// possible in the given number of seconds.
+    pub fn new_sprint(seconds: u32, due_date: Option<NaiveDate>) -> Result<Self, ConfigError> {
+        let builder = multiplication_builder(1, 1)?;
+        let mut assignment = Self::with_builder(
+            "Спринт".to_owned(),
+            sprint_description(seconds),
+            builder,
+            0,
+            due_date,
+        );
+        assignment.time_limit = Some(seconds);
+        Ok(assignment)
+    }
+
+    /// Multiplication practice focused on the given times tables: one factor
+    /// is always one of `tables`, the other is a single digit.
+    pub fn new_times_tables(
+        tables: Vec<i32>,
+        num_tasks: i32,
+        due_date: Option<NaiveDate>,
+    ) -> Result<Self, ConfigError> {
+        let description = tables_description(&tables);
+        let builder = TaskBuilder {
+            mode: TaskBuilderMode::Multiplication,
+            xrange: OperandRange::new(2, 9),
+            yrange: OperandRange::new(2, 9),
+            carry: Carry::Any,
+            unknowns: None,
+            xvalues: Some(tables),
+            yvalues: None,
+            sampling: Sampling::Uniform,
+        }
+        .validated()?;
+        Ok(Self::with_builder(
+            "Таблица за Умножение".to_owned(),
+            description,
+            builder,
+            num_tasks,
+            due_date,
+        ))
+    }
+
+    pub fn new_addition(
+        xdigits: u32,
+        ydigits: u32,
+        carry: Carry,
+        num_tasks: i32,
+        due_date: Option<NaiveDate>,
+    ) -> Result<Self, ConfigError> {
+        let builder = addition_builder(xdigits, ydigits, carry)?;
+        Ok(Self::with_builder(
+            "Събиране и Изваждане".to_owned(),
+            addition_description(xdigits, ydigits, carry),
+            builder,
+            num_tasks,
+            due_date,
+        ))
+    }
+
+    /// Chains of `terms` numbers with `digits` digits each, added and
+    /// subtracted without going below zero.
+    pub fn new_chain(
+        terms: u32,
+        digits: u32,
+        num_tasks: i32,
+        due_date: Option<NaiveDate>,
+    ) -> Result<Self, ConfigError> {
+        let builder = TaskBuilder {
+            mode: TaskBuilderMode::Chain {
+                terms,
+                negative: false,
+            },
+            xrange: OperandRange::try_digits(digits)?,
+            yrange: OperandRange::try_digits(digits)?,
+            carry: Carry::Any,
+            unknowns: None,
+            xvalues: None,
+            yvalues: None,
+            sampling: Sampling::Uniform,
+        }
+        .validated()?;
+        Ok(Self::with_builder(
+            "Верижно Смятане".to_owned(),
+            chain_description(terms, digits),
+            builder,
+            num_tasks,
+            due_date,
+        ))
+    }
+
+    /// Flash anzan with `count` numbers of `digits` digits, each shown for
+    /// `interval` milliseconds.
+    pub fn new_flash_anzan(
+        count: u32,
+        digits: u32,
+        interval: u32,
+        num_tasks: i32,
+        due_date: Option<NaiveDate>,
+    ) -> Result<Self, ConfigError> {
         let builder = TaskBuilder {
-            mode: TaskBuilderMode::AdditionAndSubtraction,
-            xrange: Uniform::new(1, 10),
-            yrange: Uniform::new(1, 10),
+            mode: TaskBuilderMode::FlashAnzan { count, interval },
+            xrange: OperandRange::try_digits(digits)?,
+            yrange: OperandRange::try_digits(digits)?,
+            carry: Carry::Any,
+            unknowns: None,
+            xvalues: None,
+            yvalues: None,
+            sampling: Sampling::Uniform,
+        }
+        .validated()?;
+        Ok(Self::with_builder(
+            "Флаш Анзан".to_owned(),
+            flash_anzan_description(count, digits, interval),
+            builder,
+            num_tasks,
+            due_date,
+        ))
+    }
+
+    pub fn new_soroban_reading(
+        digits: u32,
+        num_tasks: i32,
+        due_date: Option<NaiveDate>,
+    ) -> Result<Self, ConfigError> {
+        let builder = TaskBuilder {
+            mode: TaskBuilderMode::SorobanReading,
+            xrange: OperandRange::new(0, OperandRange::try_digits(digits)?.max),
+            yrange: OperandRange::new(0, 0),
+            carry: Carry::Any,
+            unknowns: None,
+            xvalues: None,
+            yvalues: None,
+            sampling: Sampling::Uniform,
+        }
+        .validated()?;
+        let mut assignment = Self::with_builder(
+            "Четене от Соробан".to_owned(),
+            format!("до {} числа", digits_plural(digits)),
+            builder,
+            num_tasks,
+            due_date,
+        );
+        assignment.abacus = true;
+        Ok(assignment)
+    }
+
+    /// Complements to `target` ("small friends" for 5, "big friends" for 10).
+    pub fn new_complements(
+        target: i32,
+        num_tasks: i32,
+        due_date: Option<NaiveDate>,
+    ) -> Result<Self, ConfigError> {
+        let builder = TaskBuilder {
+            mode: TaskBuilderMode::Complements { target },
+            xrange: OperandRange::new(1, target.saturating_sub(1)),
+            yrange: OperandRange::new(1, target.saturating_sub(1)),
+            carry: Carry::Any,
+            unknowns: None,
+            xvalues: None,
+            yvalues: None,
+            sampling: Sampling::Uniform,
+        }
+        .validated()?;
+        Ok(Self::with_builder(
+            complements_title(target),
+            format!("допълване до {}", target),
+            builder,
+            num_tasks,
+            due_date,
+        ))
+    }
+
+    /// Single digit tasks of the given mode that ask for an operand or the
+    /// operator as often as the weights say, e.g. `7 x ? = 56` or `8 ? 7 = 56`.
+    pub fn new_missing(
+        mode: TaskBuilderMode,
+        unknowns: UnknownWeights,
+        num_tasks: i32,
+        due_date: Option<NaiveDate>,
+    ) -> Result<Self, ConfigError> {
+        let (title, low) = match mode {
+            TaskBuilderMode::Multiplication => ("Умножение", 2),
+            TaskBuilderMode::Division => ("Деление", 2),
+            _ => ("Събиране и Изваждане", 1),
         };
-        Self {
-            id: Uuid::new_v4(),
+        let builder = TaskBuilder {
+            mode,
+            xrange: OperandRange::new(low, 9),
+            yrange: OperandRange::new(low, 9),
+            carry: Carry::Any,
+            unknowns: Some(unknowns),
+            xvalues: None,
+            yvalues: None,
+            sampling: Sampling::Uniform,
+        }
+        .validated()?;
+        Ok(Self::with_builder(
+            title.to_owned(),
+            unknowns_description(&unknowns),
+            builder,
+            num_tasks,
             due_date,
-            timed: true,
-            title: "Събиране и Изваждане".to_owned(),
-            description: "едноцифрено с едноцифрено".to_owned(),
+        ))
+    }
+
+    /// Review of the given facts, e.g. the ones due for spaced repetition,
+    /// up to `MAX_REVIEW_FACTS` of them.
+    pub fn new_review(
+        mut facts: Vec<Fact>,
+        num_tasks: i32,
+        due_date: Option<NaiveDate>,
+    ) -> Result<Self, ConfigError> {
+        // The rest wait for the next review.
+        facts.truncate(MAX_REVIEW_FACTS);
+        let description = format!("{} факта за преговор", facts.len());
+        let builder = TaskBuilder {
+            // Every fact comes up before any comes up again.
+            sampling: Sampling::Deck { commutative: false },
+            ..TaskBuilder::without_operands(TaskBuilderMode::Review { facts })
+        }
+        .validated()?;
+        Ok(Self::with_builder(
+            "Преговор".to_owned(),
+            description,
+            builder,
             num_tasks,
-            tasks: Vec::new(),
-            builder: builder,
+            due_date,
+        ))
+    }
+
+    /// A single stream of tasks drawn from the given builders, each picked
+    /// with its weight and named by its title in the description.
+    pub fn new_mix(
+        parts: Vec<(u32, TaskBuilder, &str)>,
+        num_tasks: i32,
+        due_date: Option<NaiveDate>,
+    ) -> Result<Self, ConfigError> {
+        let description = mix_description(
+            &parts
+                .iter()
+                .map(|(weight, _, title)| (*weight, *title))
+                .collect::<Vec<_>>(),
+        );
+        let parts: Vec<MixPart> = parts
+            .into_iter()
+            .filter(|(weight, _, _)| *weight > 0)
+            .map(|(weight, builder, _)| MixPart { weight, builder })
+            .collect();
+        let builder = TaskBuilder::without_operands(TaskBuilderMode::Mix { parts }).validated()?;
+        Ok(Self::with_builder(
+            "Смесено".to_owned(),
+            description,
+            builder,
+            num_tasks,
+            due_date,
+        ))
+    }
+
+    /// An assignment made of sections that are done in order, e.g. 20
+    /// multiplications, then 20 additions, then 10 divisions.
+    pub fn new_composite(
+        title: String,
+        sections: Vec<Section>,
+        due_date: Option<NaiveDate>,
+    ) -> Result<Self, ConfigError> {
+        let description = sections
+            .iter()
+            .map(|s| format!("{} {}", s.num_tasks, s.title.to_lowercase()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        for section in &sections {
+            section.builder.validate()?;
+        }
+        let builder = sections
+            .first()
+            .map(|s| s.builder.clone())
+            .ok_or(ConfigError::NoSections)?;
+        let num_tasks = sections.iter().map(|s| s.num_tasks).sum();
+        let mut assignment = Self::with_builder(title, description, builder, num_tasks, due_date);
+        assignment.sections = sections;
+        Ok(assignment)
+    }
+
+    pub fn new_sd_sd_division(
+        num_tasks: i32,
+        due_date: Option<NaiveDate>,
+    ) -> Result<Self, ConfigError> {
+        let builder = division_builder()?;
+        Ok(Self::with_builder(
+            "Деление".to_owned(),
+            "в таблицата за умножение".to_owned(),
+            builder,
+            num_tasks,
+            due_date,
+        ))
+    }
+
+    pub fn new_sd_sd_division_with_remainder(
+        num_tasks: i32,
+        due_date: Option<NaiveDate>,
+    ) -> Result<Self, ConfigError> {
+        let builder = TaskBuilder {
+            mode: TaskBuilderMode::DivisionWithRemainder,
+            xrange: OperandRange::new(2, 9),
+            yrange: OperandRange::new(2, 9),
+            carry: Carry::Any,
+            unknowns: None,
+            xvalues: None,
+            yvalues: None,
+            sampling: Sampling::Uniform,
         }
+        .validated()?;
+        Ok(Self::with_builder(
+            "Деление с остатък".to_owned(),
+            "с едноцифрено частно и делител".to_owned(),
+            builder,
+            num_tasks,
+            due_date,
+        ))
     }
 
     pub fn title(&self) -> String {
@@ -59,61 +500,447 @@ impl Assignment {
     }
 
     pub fn description(&self) -> String {
+        if !self.sections.is_empty() {
+            let sections: Vec<String> = self
+                .sections
+                .iter()
+                .zip(self.section_progress())
+                .map(|(s, p)| format!("{} {}/{}", s.title, p.correct, s.num_tasks))
+                .collect();
+            return format!("{} - [ {} ]", self.description, sections.join(" | "));
+        }
         let (correct, wrong) = self.score();
+        let total = match self.time_limit {
+            Some(seconds) if self.num_tasks == 0 => format!("{} сек.", seconds),
+            _ => self.num_tasks.to_string(),
+        };
         format!(
             "{} - [ {} ✓ ] [ {} ✗ ] / [ {} ]",
-            self.description, correct, wrong, self.num_tasks
+            self.description, correct, wrong, total
         )
     }
 
     pub fn submit_task(&mut self, task: Task) {
         if !self.is_done() {
             self.tasks.push(task);
+            self.pending = None;
+            if self.criteria.on_failure == OnFailure::Restart && self.failed() {
+                self.round_start = self.tasks.len();
+                self.requeued.clear();
+            }
         }
     }
 
-    pub fn task(&self) -> Task {
-        self.builder.build()
+    /// The index of the section being worked on: the first one without all
+    /// its correct answers, or the last one once they are all in.
+    pub fn section(&self) -> usize {
+        (0..self.sections.len())
+            .find(|&i| {
+                let correct = self
+                    .round()
+                    .iter()
+                    .filter(|t| t.section == i && t.correct())
+                    .count() as i32;
+                correct < self.sections[i].num_tasks
+            })
+            .unwrap_or(self.sections.len().saturating_sub(1))
+    }
+
+    /// The builder of the next task.
+    pub fn current_builder(&self) -> &TaskBuilder {
+        self.sections
+            .get(self.section())
+            .map_or(&self.builder, |s| &s.builder)
+    }
+
+    /// Seconds to answer the next task in: the limit of its section, or of
+    /// the assignment.
+    pub fn current_task_limit(&self) -> Option<u32> {
+        self.sections
+            .get(self.section())
+            .and_then(|s| s.task_limit)
+            .or(self.task_limit)
+    }
+
+    /// The progress of each section in the current round.
+    pub fn section_progress(&self) -> Vec<Progress> {
+        self.sections
+            .iter()
+            .enumerate()
+            .map(|(i, section)| {
+                let tasks = self.round().iter().filter(|t| t.section == i);
+                Progress::of(tasks, section.num_tasks)
+            })
+            .collect()
+    }
+
+    /// The tasks of the current round.
+    pub fn round(&self) -> &[Task] {
+        &self.tasks[self.round_start.min(self.tasks.len())..]
+    }
+
+    /// The criteria with the values reached. A round that is restarted on
+    /// failure is checked as a whole, otherwise only its latest `num_tasks`
+    /// tasks count, so that a child can make up for a bad start.
+    pub fn check_criteria(&self) -> Vec<Criterion> {
+        let round = self.round();
+        let window = match self.criteria.on_failure {
+            OnFailure::Restart => round,
+            OnFailure::Extend => {
+                &round[round.len().saturating_sub(self.num_tasks.max(0) as usize)..]
+            }
+        };
+        self.criteria.check(window)
+    }
+
+    fn criteria_met(&self) -> bool {
+        self.check_criteria().iter().all(|c| c.met())
+    }
+
+    // Whether the round can no longer be completed: it has too many wrong
+    // answers, or it has all its correct answers but misses the criteria.
+    fn failed(&self) -> bool {
+        let (correct, _) = self.score();
+        let checks = self.check_criteria();
+        let too_many_wrong = checks
+            .iter()
+            .any(|c| matches!(c, Criterion::Wrong { .. }) && !c.met());
+        too_many_wrong || (correct >= self.num_tasks && !checks.iter().all(|c| c.met()))
+    }
+
+    /// How the assignment picks its next task.
+    pub fn sampling(&self) -> Sampling {
+        self.builder.sampling
+    }
+
+    /// Builds the pending task if there is none yet and starts its clock
+    /// the first time it is shown.
+    pub fn show_task(&mut self, history: &History) {
+        if self.is_done() {
+            return;
+        }
+        // The requeued tasks fill the last places of the section they were
+        // skipped in, or of the assignment when it has no sections.
+        let section = self.section();
+        let left = match self.sections.get(section) {
+            Some(s) => s.num_tasks - self.section_progress()[section].correct,
+            None => self.num_tasks - self.score().0,
+        };
+        let queued: Vec<usize> = (0..self.requeued.len())
+            .filter(|&i| self.requeued[i].section == section)
+            .collect();
+        let requeued = !queued.is_empty() && left <= queued.len() as i32;
+        let mut task = match self.pending.take() {
+            Some(task) => task,
+            None if requeued => self.requeued.remove(queued[0]),
+            // Saved settings that no longer pass the checks build nothing.
+            None => match self.current_builder().check() {
+                Ok(builder) => Task {
+                    section,
+                    ..builder.build(self.seed, self.tasks.len(), history)
+                },
+                Err(err) => {
+                    log::error!("cannot build the tasks of {}: {}", self.title, err);
+                    return;
+                }
+            },
+        };
+        task.t_start.get_or_insert(Utc::now().timestamp_millis());
+        self.pending = Some(task);
     }
 
     pub fn is_done(&self) -> bool {
-        self.num_tasks == (self.tasks.iter().filter(|t| t.correct()).count() as i32)
+        let (correct, _) = self.score();
+        // A sprint without a task limit only ends when the time runs out.
+        let all_correct =
+            correct >= self.num_tasks && (self.num_tasks > 0 || self.time_limit.is_none());
+        let sections_done = self
+            .sections
+            .iter()
+            .zip(self.section_progress())
+            .all(|(s, p)| p.correct >= s.num_tasks);
+        (all_correct && sections_done && self.criteria_met())
+            || self.time_left(Utc::now().timestamp_millis()) == Some(0)
+    }
+
+    /// When the first task was shown, which is when a sprint starts.
+    pub fn started(&self) -> Option<i64> {
+        self.tasks.first().or(self.pending.as_ref())?.t_start
+    }
+
+    /// The milliseconds left of a sprint at `now`, or `None` if the
+    /// assignment is not time-boxed.
+    pub fn time_left(&self, now: i64) -> Option<i64> {
+        let limit = self.time_limit? as i64 * 1000;
+        Some(match self.started() {
+            Some(start) => (start + limit - now).max(0),
+            None => limit,
+        })
+    }
+
+    /// Records an answer to the pending task at `now`. The task is taken to
+    /// be submitted, unless the answer is wrong and the assignment retries.
+    pub fn take_answered(&mut self, answer: Answer, now: i64) -> Option<Task> {
+        let task = self.pending.as_mut()?;
+        task.attempts.push(Attempt { answer, time: now });
+        if self.retry && !task.correct() {
+            return None;
+        }
+        task.t_finish = Some(now);
+        self.pending.take()
+    }
+
+    /// How many more tasks the child may skip, `None` if there is no limit.
+    pub fn skips_left(&self) -> Option<u32> {
+        let skipped = self
+            .tasks
+            .iter()
+            .filter(|t| matches!(t.state(), TaskState::Skipped))
+            .count() as u32;
+        self.max_skips.map(|max| max.saturating_sub(skipped))
+    }
+
+    /// Takes the pending task unanswered, if the child may skip it, and
+    /// requeues a fresh copy of it when skipped tasks come back.
+    pub fn take_skipped(&mut self, now: i64) -> Option<Task> {
+        if self.skips_left() == Some(0) {
+            return None;
+        }
+        let mut task = self.pending.take()?;
+        if self.requeue_skipped {
+            self.requeued.push(Task {
+                attempts: Vec::new(),
+                t_start: None,
+                ..task.clone()
+            });
+        }
+        task.t_finish = Some(now);
+        task.skipped = true;
+        Some(task)
+    }
+
+    /// The milliseconds left to answer the pending task at `now`, or `None`
+    /// if tasks have no time limit or the pending task is not shown yet.
+    pub fn task_time_left(&self, now: i64) -> Option<i64> {
+        let limit = self.current_task_limit()? as i64 * 1000;
+        let start = self.pending.as_ref()?.t_start?;
+        Some((start + limit - now).max(0))
+    }
+
+    /// Takes the pending task if its time limit has run out at `now`,
+    /// marked as timed out and ready to be submitted.
+    pub fn take_timed_out(&mut self, now: i64) -> Option<Task> {
+        if self.task_time_left(now)? > 0 {
+            return None;
+        }
+        let mut task = self.pending.take()?;
+        task.timed_out = true;
+        task.t_finish = Some(now);
+        Some(task)
+    }
+
+    /// Drops the unanswered task of a sprint whose time has run out.
+    pub fn expire(&mut self) {
+        if self.is_done() {
+            self.pending = None;
+        }
+    }
+
+    /// The result of a finished sprint.
+    pub fn sprint_summary(&self) -> Option<SprintSummary> {
+        let limit = self.time_limit? as i64 * 1000;
+        if !self.is_done() {
+            return None;
+        }
+        let (correct, wrong) = self.score();
+        // A sprint that runs out of tasks ends with the last answer, any
+        // other runs for its whole time.
+        let finished_early = self.num_tasks > 0 && correct >= self.num_tasks;
+        let millis = match (self.started(), self.tasks.last().and_then(|t| t.t_finish)) {
+            (Some(start), Some(finish)) if finished_early => (finish - start).clamp(1, limit),
+            _ => limit,
+        };
+        let answered = correct + wrong;
+        Some(SprintSummary {
+            correct,
+            answered,
+            per_minute: correct as f32 * 60_000.0 / millis as f32,
+            accuracy: if answered > 0 {
+                (correct as f32 * 100.0 / answered as f32).round() as i32
+            } else {
+                0
+            },
+        })
     }
 
     pub fn score(&self) -> (i32, i32) {
-        let correct = self.tasks.iter().filter(|t| t.correct()).count() as i32;
-        let wrong = self.tasks.iter().filter(|t| !t.correct()).count() as i32;
+        let correct = self.round().iter().filter(|t| t.correct()).count() as i32;
+        // Skipped and timed out tasks were never answered, so they are not
+        // wrong either.
+        let wrong = self
+            .round()
+            .iter()
+            .filter(|t| matches!(t.state(), TaskState::Wrong))
+            .count() as i32;
         (correct, wrong)
     }
 
     pub fn progress(&self) -> Progress {
-        let mut correct = 0;
-        let mut wrong = 0;
-        let mut skipped = 0;
-
-        self.tasks.iter().for_each(|t| {
-            match t.answer {
-                Some(_) => {
-                    if t.correct() {
-                        correct += 1;
-                    } else {
-                        wrong += 1;
-                    }
-                }
-                None => {
-                    skipped += 1;
-                }
-            };
-        });
-        let percent_done = (correct as f32 * 100.0 / self.num_tasks as f32).round() as i32;
-        Progress {
-            total: self.num_tasks,
-            correct,
-            wrong,
-            skipped,
-            percent_done,
+        let mut progress = Progress::of(self.round().iter(), self.num_tasks);
+        // A sprint is as far along as the larger of its tasks and its time.
+        if let (Some(limit), Some(left)) = (
+            self.time_limit,
+            self.time_left(Utc::now().timestamp_millis()),
+        ) {
+            let limit = limit as i64 * 1000;
+            progress.percent_done = progress
+                .percent_done
+                .max(((limit - left) * 100 / limit.max(1)) as i32);
         }
+        progress
+    }
+}
+
+/// The builder of `new_multiplication`, also used by sprints and mixes.
+pub fn multiplication_builder(xdigits: u32, ydigits: u32) -> Result<TaskBuilder, ConfigError> {
+    let factor = |digits| match digits {
+        1 => Ok(OperandRange::new(2, 9)),
+        _ => OperandRange::try_digits(digits),
+    };
+    TaskBuilder {
+        mode: TaskBuilderMode::Multiplication,
+        xrange: factor(xdigits)?,
+        yrange: factor(ydigits)?,
+        carry: Carry::Any,
+        unknowns: None,
+        xvalues: None,
+        yvalues: None,
+        sampling: Sampling::Uniform,
+    }
+    .validated()
+}
+
+/// The builder of `new_addition`, also used by mixes.
+pub fn addition_builder(
+    xdigits: u32,
+    ydigits: u32,
+    carry: Carry,
+) -> Result<TaskBuilder, ConfigError> {
+    TaskBuilder {
+        mode: TaskBuilderMode::AdditionAndSubtraction,
+        xrange: OperandRange::try_digits(xdigits)?,
+        yrange: OperandRange::try_digits(ydigits)?,
+        carry,
+        unknowns: None,
+        xvalues: None,
+        yvalues: None,
+        sampling: Sampling::Uniform,
+    }
+    .validated()
+}
+
+/// The builder of `new_sd_sd_division`, also used by mixes: the quotient
+/// and the divisor are single digits.
+pub fn division_builder() -> Result<TaskBuilder, ConfigError> {
+    TaskBuilder {
+        mode: TaskBuilderMode::Division,
+        xrange: OperandRange::new(2, 9),
+        yrange: OperandRange::new(2, 9),
+        carry: Carry::Any,
+        unknowns: None,
+        xvalues: None,
+        yvalues: None,
+        sampling: Sampling::Uniform,
+    }
+    .validated()
+}
+
+pub fn digits_name(digits: u32) -> &'static str {
+    match digits {
+        1 => "едноцифрено",
+        2 => "двуцифрено",
+        3 => "трицифрено",
+        4 => "четирицифрено",
+        _ => "многоцифрено",
+    }
+}
+
+pub fn multiplication_description(xdigits: u32, ydigits: u32) -> String {
+    format!("{} по {}", digits_name(xdigits), digits_name(ydigits))
+}
+
+pub fn addition_description(xdigits: u32, ydigits: u32, carry: Carry) -> String {
+    let carry = match carry {
+        Carry::Any => "",
+        Carry::Required => " с преминаване",
+        Carry::Forbidden => " без преминаване",
+    };
+    format!(
+        "{} с {}{}",
+        digits_name(xdigits),
+        digits_name(ydigits),
+        carry
+    )
+}
+
+/// The plural adjective for numbers with `digits` digits, e.g. "двуцифрени".
+pub fn digits_plural(digits: u32) -> String {
+    format!("{}и", digits_name(digits).trim_end_matches('о'))
+}
+
+pub fn chain_description(terms: u32, digits: u32) -> String {
+    format!("{} {} числа", terms, digits_plural(digits))
+}
+
+pub fn flash_anzan_description(count: u32, digits: u32, interval: u32) -> String {
+    format!(
+        "{} през {:.2} сек.",
+        chain_description(count, digits),
+        interval as f32 / 1000_f32
+    )
+}
+
+/// The share of each kind of task in a mix, e.g. "50% умножение, 50% деление".
+pub fn mix_description(parts: &[(u32, &str)]) -> String {
+    let total: u32 = parts.iter().map(|(weight, _)| weight).sum();
+    parts
+        .iter()
+        .filter(|(weight, _)| *weight > 0)
+        .map(|(weight, title)| format!("{}% {}", weight * 100 / total.max(1), title.to_lowercase()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn sprint_description(seconds: u32) -> String {
+    format!("колкото повече верни примера за {} сек.", seconds)
+}
+
+pub fn complements_title(target: i32) -> String {
+    match target {
+        5 => "Малки Приятели".to_owned(),
+        10 => "Големи Приятели".to_owned(),
+        _ => format!("Приятели на {}", target),
+    }
+}
+
+pub fn tables_description(tables: &[i32]) -> String {
+    let names: Vec<String> = tables.iter().map(|t| t.to_string()).collect();
+    match names.split_last() {
+        Some((last, [])) => format!("таблицата с {}", last),
+        Some((last, rest)) => format!("таблиците с {} и {}", rest.join(", "), last),
+        None => "без таблици".to_owned(),
+    }
+}
+
+pub fn unknowns_description(unknowns: &UnknownWeights) -> String {
+    let number = unknowns.x + unknowns.y > 0;
+    let operator = unknowns.operator > 0;
+    match (number, operator) {
+        (true, true) => "липсващо число или знак",
+        (false, true) => "липсващ знак",
+        _ => "липсващо число",
     }
+    .to_owned()
 }
 
 #[derive(PartialEq)]
@@ -122,5 +949,47 @@ pub struct Progress {
     pub correct: i32,
     pub wrong: i32,
     pub skipped: i32,
+    pub timed_out: i32,
+    /// Wrong answers given before a task was answered correctly.
+    pub retries: i32,
     pub percent_done: i32,
 }
+
+impl Progress {
+    /// Counts the tasks by their state, towards `total` correct answers.
+    pub fn of<'a>(tasks: impl Iterator<Item = &'a Task>, total: i32) -> Self {
+        let mut progress = Self {
+            total,
+            correct: 0,
+            wrong: 0,
+            skipped: 0,
+            timed_out: 0,
+            retries: 0,
+            percent_done: 0,
+        };
+        tasks.for_each(|t| match t.state() {
+            TaskState::Correct => progress.correct += 1,
+            TaskState::CorrectAfter(attempts) => {
+                progress.correct += 1;
+                progress.retries += attempts as i32 - 1;
+            }
+            TaskState::Wrong => progress.wrong += 1,
+            TaskState::Skipped => progress.skipped += 1,
+            TaskState::TimedOut => progress.timed_out += 1,
+        });
+        if total > 0 {
+            progress.percent_done =
+                ((progress.correct as f32 * 100.0 / total as f32).round() as i32).min(100);
+        }
+        progress
+    }
+}
+
+#[derive(PartialEq)]
+pub struct SprintSummary {
+    pub correct: i32,
+    pub answered: i32,
+    pub per_minute: f32,
+    /// The percentage of answered tasks that were correct.
+    pub accuracy: i32,
+}
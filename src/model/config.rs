@@ -0,0 +1,235 @@
+use std::fmt::Display;
+
+use super::{
+    operand::{Carry, OperandRange},
+    task::{CheckedBuilder, TaskBuilder, TaskBuilderMode},
+};
+
+/// The largest number of digits an operand can have and still fit an `i32`.
+pub const MAX_DIGITS: u32 = 9;
+
+/// Which operand of a task a setting is about.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operand {
+    X,
+    Y,
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operand::X => write!(f, "първото число"),
+            Operand::Y => write!(f, "второто число"),
+        }
+    }
+}
+
+/// Why the settings of a builder were rejected.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConfigError {
+    /// Operands have from 1 to `MAX_DIGITS` digits.
+    Digits(u32),
+    /// The range has no values, its minimum is above its maximum.
+    EmptyRange {
+        operand: Operand,
+        min: i32,
+        max: i32,
+    },
+    /// An empty list of explicit values, e.g. no times tables chosen.
+    NoValues(Operand),
+    /// A negative operand where the answers must not go below zero.
+    NegativeOperand { operand: Operand, min: i32 },
+    /// The divisor could be zero or negative.
+    Divisor(i32),
+    /// Some answers would not fit an `i32`.
+    Overflow,
+    /// Complements need a target of at least 2 and operands below it.
+    Target(i32),
+    /// Flash anzan shows each number for at least a millisecond.
+    Interval,
+    /// A review without any facts to review.
+    NoFacts,
+    /// A design without any operation to practice.
+    NoOperations,
+    /// A mix none of whose parts has a weight.
+    EmptyMix,
+    /// A composite assignment without sections.
+    NoSections,
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            ConfigError::Digits(digits) => write!(
+                f,
+                "Числата може да са с от 1 до {} цифри, а не с {}",
+                MAX_DIGITS, digits
+            ),
+            ConfigError::EmptyRange { operand, min, max } => {
+                write!(f, "Няма числа от {} до {} за {}", min, max, operand)
+            }
+            ConfigError::NoValues(operand) => write!(f, "Не са избрани числа за {}", operand),
+            ConfigError::NegativeOperand { operand, min } => write!(
+                f,
+                "Отговорите може да станат отрицателни, {} стига до {}",
+                operand, min
+            ),
+            ConfigError::Divisor(min) => {
+                write!(f, "Делителят трябва да е поне 1, а може да е {}", min)
+            }
+            ConfigError::Overflow => write!(f, "Отговорите може да станат твърде големи"),
+            ConfigError::Target(target) => {
+                write!(f, "Не може да се допълва до {}", target)
+            }
+            ConfigError::Interval => write!(f, "Числата трябва да се показват поне 1 мсек."),
+            ConfigError::NoFacts => write!(f, "Няма факти за преговор"),
+            ConfigError::NoOperations => write!(f, "Не е избрано действие"),
+            ConfigError::EmptyMix => write!(f, "Поне една част на смесеното трябва да има дял"),
+            ConfigError::NoSections => write!(f, "Задачата няма части"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl OperandRange {
+    /// Like `new`, but rejects a range without values.
+    pub fn try_new(operand: Operand, min: i32, max: i32) -> Result<Self, ConfigError> {
+        if min > max {
+            return Err(ConfigError::EmptyRange { operand, min, max });
+        }
+        Ok(Self::new(min, max))
+    }
+
+    /// Like `digits`, but rejects digit counts an `i32` can't hold.
+    pub fn try_digits(digits: u32) -> Result<Self, ConfigError> {
+        if !(1..=MAX_DIGITS).contains(&digits) {
+            return Err(ConfigError::Digits(digits));
+        }
+        Ok(Self::digits(digits))
+    }
+}
+
+impl TaskBuilder {
+    /// Returns the builder if it can build its tasks without panicking or
+    /// giving answers out of bounds, otherwise what is wrong with it.
+    pub fn validated(self) -> Result<Self, ConfigError> {
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// Checks the builder for building tasks, see [`CheckedBuilder`].
+    pub fn check(&self) -> Result<CheckedBuilder<'_>, ConfigError> {
+        self.validate()?;
+        Ok(CheckedBuilder::new(self))
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let x = || bounds(Operand::X, &self.xrange, &self.xvalues);
+        let y = || bounds(Operand::Y, &self.yrange, &self.yvalues);
+        match self.mode {
+            TaskBuilderMode::Multiplication => {
+                fits(magnitude(x()?).saturating_mul(magnitude(y()?)))
+            }
+            TaskBuilderMode::AdditionAndSubtraction => {
+                let (x, y) = (
+                    non_negative(Operand::X, x()?)?,
+                    non_negative(Operand::Y, y()?)?,
+                );
+                // With a carry constraint the operands are built digit by
+                // digit, with as many digits as the largest in the range.
+                if self.carry != Carry::Any {
+                    for range in [self.xrange, self.yrange] {
+                        if range.digit_count() > MAX_DIGITS {
+                            return Err(ConfigError::Digits(range.digit_count()));
+                        }
+                    }
+                }
+                fits(magnitude(x) + magnitude(y))
+            }
+            TaskBuilderMode::Division | TaskBuilderMode::DivisionWithRemainder => {
+                let (x, y) = (x()?, y()?);
+                if y.0 < 1 {
+                    return Err(ConfigError::Divisor(y.0));
+                }
+                // The remainder is below the divisor.
+                fits(magnitude(x).saturating_mul(magnitude(y)) + magnitude(y))
+            }
+            TaskBuilderMode::Chain { terms, negative } => {
+                let (mut x, mut y) = (x()?, y()?);
+                if !negative {
+                    x = non_negative(Operand::X, x)?;
+                    y = non_negative(Operand::Y, y)?;
+                }
+                fits(magnitude(x) + magnitude(y).saturating_mul(terms.max(2) as i64))
+            }
+            TaskBuilderMode::FlashAnzan { count, interval } => {
+                if interval == 0 {
+                    return Err(ConfigError::Interval);
+                }
+                let x = non_negative(Operand::X, x()?)?;
+                fits(magnitude(x).saturating_mul(count.max(2) as i64))
+            }
+            TaskBuilderMode::SorobanReading => non_negative(Operand::X, x()?).map(|_| ()),
+            TaskBuilderMode::Complements { target } => {
+                // Checked before the operands, which are drawn below it.
+                if target < 2 {
+                    return Err(ConfigError::Target(target));
+                }
+                let (min, max) = x()?;
+                if min < 1 || max >= target {
+                    return Err(ConfigError::Target(target));
+                }
+                Ok(())
+            }
+            TaskBuilderMode::Review { ref facts } => {
+                if facts.is_empty() {
+                    return Err(ConfigError::NoFacts);
+                }
+                Ok(())
+            }
+            TaskBuilderMode::Mix { ref parts } => {
+                if parts.iter().all(|p| p.weight == 0) {
+                    return Err(ConfigError::EmptyMix);
+                }
+                parts
+                    .iter()
+                    .filter(|p| p.weight > 0)
+                    .try_for_each(|p| p.builder.validate())
+            }
+        }
+    }
+}
+
+// The smallest and the largest value the builder can draw for an operand.
+fn bounds(
+    operand: Operand,
+    range: &OperandRange,
+    values: &Option<Vec<i32>>,
+) -> Result<(i32, i32), ConfigError> {
+    match values {
+        Some(values) => match (values.iter().min(), values.iter().max()) {
+            (Some(min), Some(max)) => Ok((*min, *max)),
+            _ => Err(ConfigError::NoValues(operand)),
+        },
+        None => OperandRange::try_new(operand, range.min, range.max).map(|r| (r.min, r.max)),
+    }
+}
+
+fn non_negative(operand: Operand, (min, max): (i32, i32)) -> Result<(i32, i32), ConfigError> {
+    if min < 0 {
+        return Err(ConfigError::NegativeOperand { operand, min });
+    }
+    Ok((min, max))
+}
+
+fn magnitude((min, max): (i32, i32)) -> i64 {
+    (min as i64).abs().max((max as i64).abs())
+}
+
+fn fits(value: i64) -> Result<(), ConfigError> {
+    if value > i32::MAX as i64 {
+        return Err(ConfigError::Overflow);
+    }
+    Ok(())
+}
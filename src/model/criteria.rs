@@ -0,0 +1,159 @@
+use serde_derive::{Deserialize, Serialize};
+
+use super::task::Task;
+
+/// Conditions an assignment has to meet, besides the number of correct
+/// answers, before it is done.
+#[derive(Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct Criteria {
+    /// The minimum percentage of correct answers.
+    pub min_accuracy: Option<u32>,
+    /// The maximum average number of seconds to answer a task.
+    pub max_average_secs: Option<u32>,
+    /// The maximum number of wrong answers.
+    pub max_wrong: Option<u32>,
+    pub on_failure: OnFailure,
+}
+
+/// What happens when the correct answers are in but the criteria are not met.
+#[derive(Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum OnFailure {
+    /// Keep going until the latest tasks meet the criteria.
+    #[default]
+    Extend,
+    /// Start over with a new round of tasks.
+    Restart,
+}
+
+/// A criterion together with the value reached so far.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Criterion {
+    Accuracy { min: u32, actual: u32 },
+    AverageTime { max: u32, actual: f32 },
+    Wrong { max: u32, actual: u32 },
+}
+
+impl Criterion {
+    pub fn met(&self) -> bool {
+        match *self {
+            Criterion::Accuracy { min, actual } => actual >= min,
+            Criterion::AverageTime { max, actual } => actual <= max as f32,
+            Criterion::Wrong { max, actual } => actual <= max,
+        }
+    }
+}
+
+impl Criteria {
+    pub fn is_empty(&self) -> bool {
+        self.min_accuracy.is_none() && self.max_average_secs.is_none() && self.max_wrong.is_none()
+    }
+
+    /// Checks the criteria against the given tasks. Every attempt counts, so
+    /// guessing until the answer is right still lowers the accuracy, and
+    /// running out of time counts as a wrong answer.
+    pub fn check(&self, tasks: &[Task]) -> Vec<Criterion> {
+        let correct = tasks.iter().filter(|t| t.correct()).count() as u32;
+        let answers = tasks
+            .iter()
+            .map(|t| t.attempts.len() as u32 + t.timed_out as u32)
+            .sum::<u32>();
+        let wrong = answers - correct;
+        let times: Vec<i64> = tasks
+            .iter()
+            .filter(|t| !t.attempts.is_empty())
+            .filter_map(|t| Some(t.t_finish? - t.t_start?))
+            .collect();
+        let average_secs = if times.is_empty() {
+            0.0
+        } else {
+            times.iter().sum::<i64>() as f32 / times.len() as f32 / 1000.0
+        };
+
+        let accuracy = self.min_accuracy.map(|min| Criterion::Accuracy {
+            min,
+            actual: if answers > 0 {
+                correct * 100 / answers
+            } else {
+                0
+            },
+        });
+        let average_time = self.max_average_secs.map(|max| Criterion::AverageTime {
+            max,
+            actual: average_secs,
+        });
+        let wrong = self
+            .max_wrong
+            .map(|max| Criterion::Wrong { max, actual: wrong });
+        [accuracy, average_time, wrong]
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::task::{Answer, Attempt, Operation};
+
+    // A 2 x 3 task answered with `answers` over `millis` milliseconds.
+    fn task(answers: &[i32], millis: i64) -> Task {
+        let mut task = Task::new(2, 3, Operation::Multiplication);
+        task.attempts = answers
+            .iter()
+            .map(|&answer| Attempt {
+                answer: Answer::Number(answer),
+                time: millis,
+            })
+            .collect();
+        task.t_start = Some(0);
+        task.t_finish = Some(millis);
+        task
+    }
+
+    #[test]
+    fn every_attempt_counts() {
+        let criteria = Criteria {
+            min_accuracy: Some(50),
+            max_average_secs: Some(3),
+            max_wrong: Some(1),
+            on_failure: OnFailure::Extend,
+        };
+        let tasks = [task(&[6], 2000), task(&[5, 6], 4000)];
+        let result = criteria.check(&tasks);
+        assert!(
+            result
+                == vec![
+                    Criterion::Accuracy {
+                        min: 50,
+                        actual: 66
+                    },
+                    Criterion::AverageTime {
+                        max: 3,
+                        actual: 3.0
+                    },
+                    Criterion::Wrong { max: 1, actual: 1 },
+                ]
+        );
+        assert!(result.iter().all(Criterion::met));
+    }
+
+    #[test]
+    fn timing_out_counts_as_wrong() {
+        let criteria = Criteria {
+            max_wrong: Some(0),
+            ..Default::default()
+        };
+        let mut timed_out = task(&[], 10_000);
+        timed_out.timed_out = true;
+        let result = criteria.check(&[task(&[6], 1000), timed_out]);
+        assert!(result == vec![Criterion::Wrong { max: 0, actual: 1 }]);
+        assert!(!result[0].met());
+    }
+
+    #[test]
+    fn no_criteria_check_nothing() {
+        assert!(Criteria::default().is_empty());
+        assert!(Criteria::default().check(&[task(&[5], 1000)]).is_empty());
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use serde_derive::{Deserialize, Serialize};
+
+use super::{
+    assignment::Assignment,
+    task::{Operation, Prompt, Task},
+};
+
+/// A single arithmetic fact such as 7 x 8, independent of how it was asked.
+/// The operands of commutative operations are ordered, so 8 x 7 is the same
+/// fact as 7 x 8.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fact {
+    pub op: Operation,
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Fact {
+    pub fn new(op: Operation, x: i32, y: i32) -> Self {
+        match op {
+            Operation::Addition | Operation::Multiplication if x > y => Self { op, x: y, y: x },
+            _ => Self { op, x, y },
+        }
+    }
+
+    /// The fact practiced by a task. Chains and soroban readings are not
+    /// single facts.
+    pub fn of(task: &Task) -> Option<Self> {
+        if task.prompt != Prompt::Expression || !task.chain.is_empty() {
+            return None;
+        }
+        Some(Self::new(task.op, task.x, task.y))
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct FactStats {
+    pub answered: u32,
+    pub wrong: u32,
+    pub total_millis: i64,
+}
+
+impl FactStats {
+    pub fn average_millis(&self) -> Option<f32> {
+        (self.answered > 0).then_some(self.total_millis as f32 / self.answered as f32)
+    }
+}
+
+/// How well the child knows each fact, gathered from all answered tasks.
+#[derive(Default)]
+pub struct History {
+    facts: HashMap<Fact, FactStats>,
+    average_millis: HashMap<Operation, f32>,
+}
+
+impl History {
+    pub fn new(assignments: &[Assignment]) -> Self {
+        let mut facts: HashMap<Fact, FactStats> = HashMap::new();
+        assignments
+            .iter()
+            .flat_map(|a| a.tasks.iter())
+            // Running out of time counts as a miss, skipping does not.
+            .filter(|t| !t.attempts.is_empty() || t.timed_out)
+            .for_each(|t| {
+                if let Some(fact) = Fact::of(t) {
+                    let stats = facts.entry(fact).or_default();
+                    stats.answered += 1;
+                    if !t.correct_first_try() {
+                        stats.wrong += 1;
+                    }
+                    if let (Some(start), Some(finish)) = (t.t_start, t.t_finish) {
+                        stats.total_millis += finish - start;
+                    }
+                }
+            });
+
+        let mut totals: HashMap<Operation, (i64, u32)> = HashMap::new();
+        facts.iter().for_each(|(fact, stats)| {
+            let total = totals.entry(fact.op).or_default();
+            total.0 += stats.total_millis;
+            total.1 += stats.answered;
+        });
+        let average_millis = totals
+            .into_iter()
+            .map(|(op, (millis, answered))| (op, millis as f32 / answered as f32))
+            .collect();
+
+        Self {
+            facts,
+            average_millis,
+        }
+    }
+
+    /// How much a fact needs practice. Unknown facts weigh 1, facts that
+    /// are answered wrong or slower than the average for their operation
+    /// weigh more.
+    pub fn weakness(&self, fact: &Fact) -> f32 {
+        let Some(stats) = self.facts.get(fact) else {
+            return 1.0;
+        };
+        let error_rate = stats.wrong as f32 / stats.answered as f32;
+        let slowness = match (stats.average_millis(), self.average_millis.get(&fact.op)) {
+            (Some(millis), Some(average)) if *average > 0.0 => {
+                (millis / average - 1.0).clamp(0.0, 3.0)
+            }
+            _ => 0.0,
+        };
+        1.0 + 4.0 * error_rate + 2.0 * slowness
+    }
+}
@@ -0,0 +1,136 @@
+use chrono::{Days, NaiveDate};
+use serde_derive::{Deserialize, Serialize};
+
+use super::{fact::Fact, task::Task};
+
+// Days until the next review for each Leitner box. A fact moves one box up
+// when answered correctly on or after its review day and back to the first
+// box when missed.
+const INTERVALS: [u64; 5] = [1, 2, 4, 7, 14];
+
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub struct Card {
+    pub fact: Fact,
+    pub level: usize,
+    pub due: NaiveDate,
+    /// The day of the answer that last moved the card, which is the only
+    /// answer that counts on that day.
+    #[serde(default)]
+    pub updated: Option<NaiveDate>,
+}
+
+/// A Leitner box memory model of the arithmetic facts the child has seen.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Memory {
+    pub cards: Vec<Card>,
+}
+
+impl Memory {
+    /// Updates the card of the task's fact with an answer given on `today`.
+    /// A fact is asked several times in one review, so only its first answer
+    /// of the day counts, and answering it before it is due doesn't move it
+    /// up. Skipping is not a miss.
+    pub fn record(&mut self, task: &Task, today: NaiveDate) {
+        if task.attempts.is_empty() && !task.timed_out {
+            return;
+        }
+        let Some(fact) = Fact::of(task) else {
+            return;
+        };
+        let index = match self.cards.iter().position(|c| c.fact == fact) {
+            Some(index) => index,
+            None => {
+                self.cards.push(Card {
+                    fact,
+                    level: 0,
+                    due: today,
+                    updated: None,
+                });
+                self.cards.len() - 1
+            }
+        };
+        let card = &mut self.cards[index];
+        if card.updated == Some(today) {
+            return;
+        }
+        card.level = if !task.correct_first_try() {
+            0
+        } else if card.due <= today {
+            (card.level + 1).min(INTERVALS.len() - 1)
+        } else {
+            return;
+        };
+        card.updated = Some(today);
+        card.due = today
+            .checked_add_days(Days::new(INTERVALS[card.level]))
+            .unwrap_or(today);
+    }
+
+    /// The facts due for review on the given day, the most overdue first.
+    pub fn due(&self, day: NaiveDate) -> Vec<Fact> {
+        let mut due: Vec<&Card> = self.cards.iter().filter(|c| c.due <= day).collect();
+        due.sort_by_key(|c| (c.due, c.level));
+        due.into_iter().map(|c| c.fact).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::task::{Answer, Attempt, Operation};
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+    }
+
+    // A 2 x 3 task answered with `answers`.
+    fn task(answers: &[i32]) -> Task {
+        let mut task = Task::new(2, 3, Operation::Multiplication);
+        task.attempts = answers
+            .iter()
+            .map(|&answer| Attempt {
+                answer: Answer::Number(answer),
+                time: 0,
+            })
+            .collect();
+        task
+    }
+
+    fn card(memory: &Memory) -> (usize, NaiveDate) {
+        (memory.cards[0].level, memory.cards[0].due)
+    }
+
+    #[test]
+    fn only_the_first_answer_of_the_day_counts() {
+        let mut memory = Memory::default();
+        memory.record(&task(&[6]), day(1));
+        assert_eq!(card(&memory), (1, day(3)));
+        memory.record(&task(&[6]), day(1));
+        memory.record(&task(&[5]), day(1));
+        assert_eq!(card(&memory), (1, day(3)));
+    }
+
+    #[test]
+    fn answers_before_the_review_day_do_not_promote() {
+        let mut memory = Memory::default();
+        memory.record(&task(&[6]), day(1));
+        memory.record(&task(&[6]), day(2));
+        assert_eq!(card(&memory), (1, day(3)));
+        memory.record(&task(&[6]), day(3));
+        assert_eq!(card(&memory), (2, day(7)));
+        memory.record(&task(&[5, 6]), day(4));
+        assert_eq!(card(&memory), (0, day(5)));
+    }
+
+    #[test]
+    fn skipping_is_not_a_miss() {
+        let mut memory = Memory::default();
+        memory.record(&task(&[6]), day(1));
+        memory.record(&task(&[]), day(3));
+        assert_eq!(card(&memory), (1, day(3)));
+        let mut timed_out = task(&[]);
+        timed_out.timed_out = true;
+        memory.record(&timed_out, day(3));
+        assert_eq!(card(&memory), (0, day(4)));
+    }
+}
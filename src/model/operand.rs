@@ -0,0 +1,229 @@
+use rand::Rng;
+use serde_derive::{Deserialize, Serialize};
+
+/// An inclusive range of values an operand is drawn from.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(from = "OperandRangeRepr")]
+pub struct OperandRange {
+    pub min: i32,
+    pub max: i32,
+}
+
+// Builders saved by earlier versions stored a `Uniform<i32>`, which
+// serializes as its lower bound and the number of values in the range.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OperandRangeRepr {
+    Range { min: i32, max: i32 },
+    Uniform { low: i32, range: i32 },
+}
+
+impl From<OperandRangeRepr> for OperandRange {
+    fn from(repr: OperandRangeRepr) -> Self {
+        match repr {
+            OperandRangeRepr::Range { min, max } => Self { min, max },
+            OperandRangeRepr::Uniform { low, range } => Self {
+                min: low,
+                max: low + range - 1,
+            },
+        }
+    }
+}
+
+impl OperandRange {
+    pub fn new(min: i32, max: i32) -> Self {
+        Self { min, max }
+    }
+
+    /// All numbers with exactly `digits` digits, e.g. 10..=99 for two digits.
+    pub fn digits(digits: u32) -> Self {
+        Self {
+            min: 10_i32.pow(digits - 1),
+            max: 10_i32.pow(digits) - 1,
+        }
+    }
+
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> i32 {
+        rng.gen_range(self.min..=self.max)
+    }
+
+    /// The number of digits of the largest operand in the range.
+    pub fn digit_count(&self) -> u32 {
+        self.max.abs().max(1).ilog10() + 1
+    }
+}
+
+/// Whether an addition (subtraction) must, must not or may carry (borrow)
+/// between the digit columns.
+#[derive(Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum Carry {
+    #[default]
+    Any,
+    Required,
+    Forbidden,
+}
+
+impl Carry {
+    /// Whether an addition (subtraction) that does or doesn't carry (borrow)
+    /// meets the constraint.
+    pub fn allows(self, carries: bool) -> bool {
+        match self {
+            Carry::Any => true,
+            Carry::Required => carries,
+            Carry::Forbidden => !carries,
+        }
+    }
+}
+
+/// Whether adding the non-negative operands carries in any digit column.
+pub fn carries(x: i32, y: i32) -> bool {
+    (1..=9).any(|k| {
+        let m = 10_i32.pow(k);
+        x % m + y % m >= m
+    })
+}
+
+/// Whether subtracting `y` from `x` borrows in any digit column.
+pub fn borrows(x: i32, y: i32) -> bool {
+    (1..=9).any(|k| {
+        let m = 10_i32.pow(k);
+        x % m < y % m
+    })
+}
+
+/// Builds two operands with the given number of digits column by column, so
+/// that their sum satisfies the carry constraint by construction.
+pub fn addition_operands<R: Rng + ?Sized>(
+    rng: &mut R,
+    xdigits: u32,
+    ydigits: u32,
+    carry: Carry,
+) -> (i32, i32) {
+    let forced = rng.gen_range(0..xdigits.min(ydigits));
+    let mut x = 0;
+    let mut y = 0;
+    for column in (0..xdigits.max(ydigits)).rev() {
+        let (xlow, xhigh) = column_bounds(column, xdigits);
+        let (ylow, yhigh) = column_bounds(column, ydigits);
+        let (a, b) = match carry {
+            Carry::Required if column == forced => {
+                let a = rng.gen_range(xlow.max(1)..=xhigh);
+                (a, rng.gen_range(ylow.max(10 - a)..=yhigh))
+            }
+            Carry::Forbidden => {
+                let a = rng.gen_range(xlow..=xhigh.min(9 - ylow));
+                (a, rng.gen_range(ylow..=yhigh.min(9 - a)))
+            }
+            _ => (rng.gen_range(xlow..=xhigh), rng.gen_range(ylow..=yhigh)),
+        };
+        x = x * 10 + a;
+        y = y * 10 + b;
+    }
+    (x, y)
+}
+
+/// Whether a subtraction of a `ydigits` number from a `xdigits` number can
+/// borrow without going negative.
+pub fn can_borrow(xdigits: u32, ydigits: u32) -> bool {
+    xdigits > 1 && ydigits > 0
+}
+
+/// Builds a minuend with `xdigits` digits and a subtrahend with `ydigits`
+/// digits (`xdigits >= ydigits`) column by column, so that the difference is
+/// never negative and satisfies the borrow constraint by construction.
+///
+/// A required borrow is only possible when [`can_borrow`] holds.
+pub fn subtraction_operands<R: Rng + ?Sized>(
+    rng: &mut R,
+    xdigits: u32,
+    ydigits: u32,
+    carry: Carry,
+) -> (i32, i32) {
+    // The leading column of the minuend cannot borrow, it has to absorb it.
+    let forced = rng.gen_range(0..ydigits.min(xdigits - 1).max(1));
+    let mut x = 0;
+    let mut y = 0;
+    for column in (0..xdigits).rev() {
+        let (xlow, xhigh) = column_bounds(column, xdigits);
+        let (ylow, yhigh) = column_bounds(column, ydigits);
+        let leading = column == xdigits - 1 && xdigits == ydigits;
+        let (a, b) = match carry {
+            Carry::Required if column == forced && !leading => {
+                let b = rng.gen_range(ylow.max(1)..=yhigh);
+                (rng.gen_range(xlow..b), b)
+            }
+            Carry::Required if leading => {
+                let b = rng.gen_range(ylow..=yhigh - 1);
+                (rng.gen_range(b + 1..=xhigh), b)
+            }
+            Carry::Forbidden => {
+                let b = rng.gen_range(ylow..=yhigh);
+                (rng.gen_range(xlow.max(b)..=xhigh), b)
+            }
+            _ => (rng.gen_range(xlow..=xhigh), rng.gen_range(ylow..=yhigh)),
+        };
+        x = x * 10 + a;
+        y = y * 10 + b;
+    }
+    if x < y {
+        (y, x)
+    } else {
+        (x, y)
+    }
+}
+
+// The digits allowed in the given column of a number with `digits` digits.
+fn column_bounds(column: u32, digits: u32) -> (i32, i32) {
+    if column >= digits {
+        (0, 0)
+    } else if column == digits - 1 {
+        (1, 9)
+    } else {
+        (0, 9)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn addition_operands_meet_the_carry_constraint() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for (xdigits, ydigits) in [(1, 1), (2, 1), (2, 2), (3, 2), (4, 4)] {
+            for _ in 0..200 {
+                let (x, y) = addition_operands(&mut rng, xdigits, ydigits, Carry::Required);
+                assert!(carries(x, y), "{} + {} does not carry", x, y);
+                let (x, y) = addition_operands(&mut rng, xdigits, ydigits, Carry::Forbidden);
+                assert!(!carries(x, y), "{} + {} carries", x, y);
+                assert_eq!(OperandRange::new(x, x).digit_count(), xdigits);
+                assert_eq!(OperandRange::new(y, y).digit_count(), ydigits);
+            }
+        }
+    }
+
+    #[test]
+    fn subtraction_operands_meet_the_borrow_constraint() {
+        let mut rng = StdRng::seed_from_u64(2);
+        for (xdigits, ydigits) in [(2, 1), (2, 2), (3, 2), (4, 4)] {
+            for _ in 0..200 {
+                let (x, y) = subtraction_operands(&mut rng, xdigits, ydigits, Carry::Required);
+                assert!(x >= y, "{} - {} is negative", x, y);
+                assert!(borrows(x, y), "{} - {} does not borrow", x, y);
+                let (x, y) = subtraction_operands(&mut rng, xdigits, ydigits, Carry::Forbidden);
+                assert!(x >= y, "{} - {} is negative", x, y);
+                assert!(!borrows(x, y), "{} - {} borrows", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn legacy_uniform_range_loads() {
+        let range: OperandRange = serde_json::from_str(r#"{"low": 2, "range": 8}"#).unwrap();
+        assert!(range == OperandRange::new(2, 9));
+        let range: OperandRange = serde_json::from_str(r#"{"min": 10, "max": 99}"#).unwrap();
+        assert!(range == OperandRange::new(10, 99));
+    }
+}
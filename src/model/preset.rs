@@ -0,0 +1,135 @@
+use chrono::NaiveDate;
+use serde_derive::{Deserialize, Serialize};
+
+use super::{
+    assignment::Assignment,
+    config::ConfigError,
+    fact::History,
+    operand::{Carry, OperandRange},
+    task::{MixPart, Operation, Sampling, Task, TaskBuilder, TaskBuilderMode},
+};
+
+/// The settings of an assignment made in the designer.
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub struct Design {
+    /// The operations to practice. Addition also brings subtraction, and
+    /// several operations are mixed in equal parts.
+    pub operations: Vec<Operation>,
+    pub xrange: OperandRange,
+    pub yrange: OperandRange,
+    pub num_tasks: i32,
+    pub timed: bool,
+    /// Seconds to answer each task in, `None` for no limit.
+    pub task_limit: Option<u32>,
+}
+
+impl Default for Design {
+    fn default() -> Self {
+        Self {
+            operations: vec![Operation::Multiplication],
+            xrange: OperandRange::new(2, 9),
+            yrange: OperandRange::new(2, 9),
+            num_tasks: 100,
+            timed: true,
+            task_limit: None,
+        }
+    }
+}
+
+impl Design {
+    /// The builder of the tasks, or why the design can't build any. For
+    /// divisions `xrange` is the quotient and `yrange` the divisor.
+    pub fn builder(&self) -> Result<TaskBuilder, ConfigError> {
+        let builder = |mode| TaskBuilder {
+            mode,
+            xrange: self.xrange,
+            yrange: self.yrange,
+            carry: Carry::Any,
+            unknowns: None,
+            xvalues: None,
+            yvalues: None,
+            sampling: Sampling::Uniform,
+        };
+        let mut modes: Vec<TaskBuilderMode> = Vec::new();
+        for op in &self.operations {
+            let mode = match op {
+                Operation::Addition | Operation::Subtraction => {
+                    TaskBuilderMode::AdditionAndSubtraction
+                }
+                Operation::Multiplication => TaskBuilderMode::Multiplication,
+                Operation::Division => TaskBuilderMode::Division,
+                Operation::DivisionWithRemainder => TaskBuilderMode::DivisionWithRemainder,
+            };
+            if !modes.contains(&mode) {
+                modes.push(mode);
+            }
+        }
+        let builder = match modes.len() {
+            0 => return Err(ConfigError::NoOperations),
+            1 => builder(modes.remove(0)),
+            _ => TaskBuilder::without_operands(TaskBuilderMode::Mix {
+                parts: modes
+                    .into_iter()
+                    .map(|mode| MixPart {
+                        weight: 1,
+                        builder: builder(mode),
+                    })
+                    .collect(),
+            }),
+        };
+        builder.validated()
+    }
+
+    pub fn assignment(
+        &self,
+        title: String,
+        due_date: Option<NaiveDate>,
+    ) -> Result<Assignment, ConfigError> {
+        let mut assignment = Assignment::new_custom(
+            title,
+            self.description(),
+            self.builder()?,
+            self.num_tasks,
+            due_date,
+        )?;
+        assignment.timed = self.timed;
+        assignment.task_limit = self.task_limit;
+        Ok(assignment)
+    }
+
+    /// A few tasks like the ones the assignment will have.
+    pub fn preview(&self, seed: u64, count: usize) -> Result<Vec<Task>, ConfigError> {
+        let builder = self.builder()?;
+        let builder = builder.check()?;
+        Ok((0..count)
+            .map(|index| builder.build(seed, index, &History::default()))
+            .collect())
+    }
+
+    pub fn description(&self) -> String {
+        let operations: Vec<String> = self
+            .operations
+            .iter()
+            .map(|op| match op {
+                Operation::Addition | Operation::Subtraction => "+/-".to_owned(),
+                Operation::DivisionWithRemainder => ": с остатък".to_owned(),
+                op => op.to_string(),
+            })
+            .collect();
+        format!(
+            "{} с числа от {} до {} и от {} до {}",
+            operations.join(", "),
+            self.xrange.min,
+            self.xrange.max,
+            self.yrange.min,
+            self.yrange.max
+        )
+    }
+}
+
+/// A design saved under a name, to add the same assignment again later.
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub struct Preset {
+    pub name: String,
+    pub design: Design,
+}
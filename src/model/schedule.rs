@@ -0,0 +1,164 @@
+use std::collections::BTreeSet;
+
+use chrono::{Datelike, NaiveDate};
+use serde_derive::{Deserialize, Serialize};
+
+/// The kind of assignment generated as homework for a school day.
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub enum Template {
+    /// Review of the facts due by the day, or single digit multiplication
+    /// while nothing is due yet.
+    Review,
+    Multiplication,
+    Division,
+    Addition,
+    Sprint,
+    /// An assignment made in the designer, by the name of its preset. The
+    /// preset is looked up when the homework is given, so that it follows
+    /// changes to the preset.
+    Preset(String),
+}
+
+impl Template {
+    pub fn name(&self) -> String {
+        match self {
+            Template::Review => "Преговор".to_owned(),
+            Template::Multiplication => "Умножение".to_owned(),
+            Template::Division => "Деление".to_owned(),
+            Template::Addition => "Събиране".to_owned(),
+            Template::Sprint => "Спринт".to_owned(),
+            Template::Preset(name) => name.clone(),
+        }
+    }
+}
+
+/// When homework is given and what it is.
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub struct Schedule {
+    /// The homework of each day of the week, Monday first. A weekday
+    /// without templates is a day off, e.g. the weekend.
+    pub weekdays: [Vec<Template>; 7],
+    /// Holidays and other days without homework.
+    #[serde(default)]
+    pub days_off: BTreeSet<NaiveDate>,
+    /// The first day with homework, `None` to start right away.
+    #[serde(default)]
+    pub start: Option<NaiveDate>,
+    /// The last day with homework, `None` to keep going.
+    #[serde(default)]
+    pub end: Option<NaiveDate>,
+    /// How many missed days get their homework when the app is opened
+    /// after a break, `None` for all of them.
+    #[serde(default)]
+    pub max_backfill: Option<u32>,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self {
+            weekdays: std::array::from_fn(|_| vec![Template::Review]),
+            days_off: BTreeSet::new(),
+            start: None,
+            end: None,
+            max_backfill: None,
+        }
+    }
+}
+
+impl Schedule {
+    /// The homework of the day, none on days off and outside the term.
+    pub fn templates(&self, day: NaiveDate) -> &[Template] {
+        let outside = self.start.map_or(false, |start| day < start)
+            || self.end.map_or(false, |end| day > end);
+        if outside || self.days_off.contains(&day) {
+            return &[];
+        }
+        &self.weekdays[day.weekday().num_days_from_monday() as usize]
+    }
+
+    /// The days from `first` to `today` that get homework. Only the latest
+    /// `max_backfill` days before today are caught up on.
+    pub fn due_days(&self, first: NaiveDate, today: NaiveDate) -> Vec<NaiveDate> {
+        let mut missed: Vec<NaiveDate> = first
+            .iter_days()
+            .take_while(|day| *day < today)
+            .filter(|day| !self.templates(*day).is_empty())
+            .collect();
+        if let Some(max) = self.max_backfill {
+            missed.drain(..missed.len().saturating_sub(max as usize));
+        }
+        if first <= today && !self.templates(today).is_empty() {
+            missed.push(today);
+        }
+        missed
+    }
+}
+
+/// The names of the weekdays, Monday first.
+pub const WEEKDAYS: [&str; 7] = [
+    "Понеделник",
+    "Вторник",
+    "Сряда",
+    "Четвъртък",
+    "Петък",
+    "Събота",
+    "Неделя",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        // 2024-01-01 is a Monday.
+        NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+    }
+
+    // Homework on school days only.
+    fn school_days() -> Schedule {
+        let mut schedule = Schedule::default();
+        schedule.weekdays[5].clear();
+        schedule.weekdays[6].clear();
+        schedule
+    }
+
+    #[test]
+    fn skips_days_off() {
+        let mut schedule = school_days();
+        schedule.days_off.insert(day(3));
+        assert_eq!(
+            schedule.due_days(day(1), day(9)),
+            vec![day(1), day(2), day(4), day(5), day(8), day(9)]
+        );
+        // Nothing is due on a weekend today.
+        assert_eq!(schedule.due_days(day(6), day(7)), vec![]);
+    }
+
+    #[test]
+    fn keeps_to_the_term() {
+        let mut schedule = school_days();
+        schedule.start = Some(day(3));
+        schedule.end = Some(day(8));
+        assert_eq!(
+            schedule.due_days(day(1), day(10)),
+            vec![day(3), day(4), day(5), day(8)]
+        );
+    }
+
+    #[test]
+    fn backfills_only_the_latest_days() {
+        let mut schedule = school_days();
+        schedule.max_backfill = Some(2);
+        assert_eq!(
+            schedule.due_days(day(1), day(10)),
+            vec![day(8), day(9), day(10)]
+        );
+        schedule.max_backfill = Some(0);
+        assert_eq!(schedule.due_days(day(1), day(10)), vec![day(10)]);
+    }
+
+    #[test]
+    fn nothing_is_due_before_the_first_day() {
+        assert_eq!(Schedule::default().due_days(day(5), day(4)), vec![]);
+    }
+}
@@ -1,63 +1,316 @@
-use chrono::Utc;
-use rand::{distributions::Uniform, Rng};
+use rand::{distributions::WeightedIndex, rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 use serde_derive::{Deserialize, Serialize};
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 use yew::{html, Html};
 
-#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+use super::{
+    fact::{Fact, History},
+    operand::{self, Carry, OperandRange},
+};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(from = "TaskRepr")]
 pub struct Task {
     pub x: i32,
     pub y: i32,
     pub op: Operation,
-    pub answer: Option<i32>,
+    /// Further terms of a chained expression, applied left to right after
+    /// `x op y`. Empty for plain two-operand tasks.
+    #[serde(default)]
+    pub chain: Vec<Term>,
+    #[serde(default)]
+    pub prompt: Prompt,
+    #[serde(default)]
+    pub unknown: Unknown,
+    /// Every answer given to the task in order. Only the last one can be
+    /// correct, since a correct answer ends the task.
+    pub attempts: Vec<Attempt>,
     pub t_start: Option<i64>,
     pub t_finish: Option<i64>,
+    /// The task ran out of its time limit before it was answered.
+    pub timed_out: bool,
+    /// The child skipped the task, possibly after wrong answers.
+    #[serde(default)]
+    pub skipped: bool,
+    /// The index of the assignment section the task belongs to.
+    pub section: usize,
+}
+
+// Tasks saved by earlier versions held a single answer instead of attempts.
+#[derive(Deserialize)]
+struct TaskRepr {
+    x: i32,
+    y: i32,
+    op: Operation,
+    #[serde(default)]
+    chain: Vec<Term>,
+    #[serde(default)]
+    prompt: Prompt,
+    #[serde(default)]
+    unknown: Unknown,
+    #[serde(default)]
+    answer: Option<Answer>,
+    #[serde(default)]
+    attempts: Vec<Attempt>,
+    t_start: Option<i64>,
+    t_finish: Option<i64>,
+    #[serde(default)]
+    timed_out: bool,
+    #[serde(default)]
+    skipped: bool,
+    #[serde(default)]
+    section: usize,
+}
+
+impl From<TaskRepr> for Task {
+    fn from(repr: TaskRepr) -> Self {
+        let mut attempts = repr.attempts;
+        if let (true, Some(answer)) = (attempts.is_empty(), repr.answer) {
+            attempts.push(Attempt {
+                answer,
+                time: repr.t_finish.unwrap_or_default(),
+            });
+        }
+        Self {
+            x: repr.x,
+            y: repr.y,
+            op: repr.op,
+            chain: repr.chain,
+            prompt: repr.prompt,
+            unknown: repr.unknown,
+            attempts,
+            t_start: repr.t_start,
+            t_finish: repr.t_finish,
+            timed_out: repr.timed_out,
+            skipped: repr.skipped,
+            section: repr.section,
+        }
+    }
+}
+
+/// An answer given to a task and when it was given.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct Attempt {
+    pub answer: Answer,
+    pub time: i64,
 }
 
 impl Display for Task {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let op = match self.op {
-            Operation::Addition => "+",
-            Operation::Subtraction => "-",
-            Operation::Multiplication => "x",
+        if self.prompt == Prompt::Soroban {
+            return match self.answer() {
+                Some(answer) => write!(f, "Соробан {} = {}", self.x, answer),
+                None => write!(f, "Соробан = ?"),
+            };
+        }
+        let slot = |value: String, unknown: Unknown| match (self.unknown == unknown, self.answer())
+        {
+            (false, _) => value,
+            (true, Some(answer)) => answer.to_string(),
+            (true, None) => "?".to_owned(),
         };
-        match self.answer {
-            Some(answer) => {
-                write!(f, "{} {} {} = {}", self.x, op, self.y, answer)
-            }
-            None => {
-                write!(f, "{} {} {} = ?", self.x, op, self.y)
-            }
+        if self.unknown != Unknown::Result {
+            return write!(
+                f,
+                "{} {} {} = {}",
+                slot(self.x.to_string(), Unknown::X),
+                slot(self.op.to_string(), Unknown::Operator),
+                slot(self.y.to_string(), Unknown::Y),
+                self.value()
+            );
+        }
+        write!(f, "{} {} {}", self.x, self.op, self.y)?;
+        for term in &self.chain {
+            write!(f, " {} {}", term.op, term.value)?;
+        }
+        match self.answer() {
+            Some(answer) => write!(f, " = {}", answer),
+            None => write!(f, " = ?"),
         }
     }
 }
 
 impl Task {
+    /// A task asking for the result of `x op y`, not yet shown.
+    pub fn new(x: i32, y: i32, op: Operation) -> Self {
+        Self {
+            x,
+            y,
+            op,
+            chain: Vec::new(),
+            prompt: Prompt::Expression,
+            unknown: Unknown::Result,
+            attempts: Vec::new(),
+            t_start: None,
+            t_finish: None,
+            timed_out: false,
+            skipped: false,
+            section: 0,
+        }
+    }
+
+    /// The last answer given to the task.
+    pub fn answer(&self) -> Option<Answer> {
+        self.attempts.last().map(|attempt| attempt.answer)
+    }
+
+    pub fn solution(&self) -> Answer {
+        match self.unknown {
+            Unknown::X => return Answer::Number(self.x),
+            Unknown::Y => return Answer::Number(self.y),
+            Unknown::Operator => return Answer::Operator(self.op),
+            Unknown::Result => {}
+        }
+        match self.op {
+            Operation::DivisionWithRemainder => Answer::Remainder {
+                quotient: self.x / self.y,
+                remainder: self.x % self.y,
+            },
+            _ => Answer::Number(self.value()),
+        }
+    }
+
+    /// The numbers of the expression in order, with subtracted ones negated.
+    pub fn terms(&self) -> Vec<i32> {
+        let signed = |op: Operation, value: i32| match op {
+            Operation::Subtraction => -value,
+            _ => value,
+        };
+        let mut terms = vec![self.x, signed(self.op, self.y)];
+        terms.extend(self.chain.iter().map(|term| signed(term.op, term.value)));
+        terms
+    }
+
+    /// The value of the whole expression, evaluated left to right.
+    pub fn value(&self) -> i32 {
+        if self.prompt == Prompt::Soroban {
+            return self.x;
+        }
+        self.chain
+            .iter()
+            .fold(self.op.apply(self.x, self.y), |acc, term| {
+                term.op.apply(acc, term.value)
+            })
+    }
+
+    /// Whether the answer solves the task. When the operator is unknown, any
+    /// operator that gives the same result is accepted, e.g. `2 ? 2 = 4`.
+    pub fn accepts(&self, answer: Answer) -> bool {
+        match (self.unknown, answer) {
+            (Unknown::Operator, Answer::Operator(op)) => {
+                op.divides(self.x, self.y) && op.apply(self.x, self.y) == self.value()
+            }
+            _ => answer == self.solution(),
+        }
+    }
+
+    /// Whether the task was answered correctly, on any attempt.
     pub fn correct(&self) -> bool {
-        match self.answer {
-            Some(answer) => answer == self.op.apply(self.x, self.y),
+        match self.answer() {
+            Some(answer) => self.accepts(answer),
             None => false,
         }
     }
 
+    /// Whether the first answer given to the task was correct.
+    pub fn correct_first_try(&self) -> bool {
+        self.correct() && self.attempts.len() == 1
+    }
+
     pub fn state(&self) -> TaskState {
-        if let Some(answer) = self.answer {
-            if answer == self.op.apply(self.x, self.y) {
-                TaskState::Correct
-            } else {
-                TaskState::Wrong
-            }
+        if self.correct_first_try() {
+            TaskState::Correct
+        } else if self.correct() {
+            TaskState::CorrectAfter(self.attempts.len())
+        } else if self.skipped {
+            TaskState::Skipped
+        } else if self.timed_out {
+            TaskState::TimedOut
+        } else if !self.attempts.is_empty() {
+            TaskState::Wrong
         } else {
             TaskState::Skipped
         }
     }
 }
 
+/// The answer given to a task. Plain numbers are stored as-is, so answers
+/// saved before structured answers existed still load.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(untagged)]
+pub enum Answer {
+    Number(i32),
+    Remainder { quotient: i32, remainder: i32 },
+    Operator(Operation),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Remainder {
+                quotient,
+                remainder,
+            } => write!(f, "{} ост. {}", quotient, remainder),
+            Answer::Operator(op) => write!(f, "{}", op),
+        }
+    }
+}
+
+/// How a task is presented to the child.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub enum Prompt {
+    /// The expression is shown and its value is the answer.
+    #[default]
+    Expression,
+    /// `x` is shown on a soroban and has to be read back.
+    Soroban,
+}
+
+/// Which part of `x op y = z` the child has to find.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub enum Unknown {
+    #[default]
+    Result,
+    X,
+    Y,
+    Operator,
+}
+
+/// Relative weights of the [`Unknown`]s a builder asks for.
 #[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct UnknownWeights {
+    pub result: u32,
+    pub x: u32,
+    pub y: u32,
+    pub operator: u32,
+}
+
+impl UnknownWeights {
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Unknown {
+        let weights = [self.result, self.x, self.y, self.operator];
+        match WeightedIndex::new(weights) {
+            Ok(index) => {
+                [Unknown::Result, Unknown::X, Unknown::Y, Unknown::Operator][rng.sample(index)]
+            }
+            Err(_) => Unknown::Result,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct Term {
+    pub op: Operation,
+    pub value: i32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Operation {
     Addition,
     Subtraction,
     Multiplication,
+    Division,
+    DivisionWithRemainder,
 }
 
 impl Operation {
@@ -66,72 +319,523 @@ impl Operation {
             Operation::Addition => x + y,
             Operation::Subtraction => x - y,
             Operation::Multiplication => x * y,
+            Operation::Division | Operation::DivisionWithRemainder => x / y,
+        }
+    }
+
+    /// Whether `apply` is exact for these operands, i.e. it is not a division
+    /// by zero or one that leaves a remainder.
+    pub fn divides(&self, x: i32, y: i32) -> bool {
+        match self {
+            Operation::Division => y != 0 && x % y == 0,
+            Operation::DivisionWithRemainder => y != 0,
+            _ => true,
+        }
+    }
+}
+
+impl FromStr for Operation {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "+" => Ok(Operation::Addition),
+            "-" => Ok(Operation::Subtraction),
+            // Latin and Cyrillic x, as well as the usual keyboard symbols
+            "x" | "X" | "х" | "Х" | "*" | "·" | "×" => Ok(Operation::Multiplication),
+            ":" | "/" | "÷" => Ok(Operation::Division),
+            _ => Err(()),
         }
     }
 }
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Operation::Addition => "+",
+            Operation::Subtraction => "-",
+            Operation::Multiplication => "x",
+            Operation::Division | Operation::DivisionWithRemainder => ":",
+        };
+        write!(f, "{}", symbol)
+    }
+}
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
 pub enum TaskBuilderMode {
     Multiplication,
     AdditionAndSubtraction,
+    Division,
+    DivisionWithRemainder,
+    /// Chains of `terms` additions and subtractions, e.g. `a + b - c + d`.
+    Chain {
+        terms: u32,
+        negative: bool,
+    },
+    /// Flash anzan: `count` numbers from `xrange` are flashed one at a time,
+    /// each for `interval` milliseconds, and only their sum is entered.
+    FlashAnzan {
+        count: u32,
+        interval: u32,
+    },
+    /// Reading a number from `xrange` off a soroban.
+    SorobanReading,
+    /// Complements to `target`, e.g. `7 + ? = 10` or `? + 3 = 5`.
+    Complements {
+        target: i32,
+    },
+    /// Review of the given facts, e.g. the ones due in the spaced repetition
+    /// memory.
+    Review {
+        facts: Vec<Fact>,
+    },
+    /// Each task comes from one of the parts, picked by their weights, e.g.
+    /// half multiplications and half additions.
+    Mix {
+        parts: Vec<MixPart>,
+    },
+}
+
+/// A builder of a mix with its share of the tasks.
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub struct MixPart {
+    pub weight: u32,
+    pub builder: TaskBuilder,
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
 pub struct TaskBuilder {
     pub mode: TaskBuilderMode,
-    pub xrange: Uniform<i32>,
-    pub yrange: Uniform<i32>,
+    pub xrange: OperandRange,
+    pub yrange: OperandRange,
+    /// Carry/borrow constraint for additions and subtractions. When set, the
+    /// operands are built digit by digit using the digit counts of the ranges.
+    #[serde(default)]
+    pub carry: Carry,
+    /// Which part of the task is asked for; `None` uses the mode's default.
+    #[serde(default)]
+    pub unknowns: Option<UnknownWeights>,
+    /// Explicit values for `x`, e.g. the times tables to practice. When set
+    /// they are used instead of `xrange`.
+    #[serde(default)]
+    pub xvalues: Option<Vec<i32>>,
+    /// Explicit values for `y`, used instead of `yrange` when set.
+    #[serde(default)]
+    pub yvalues: Option<Vec<i32>>,
+    #[serde(default)]
+    pub sampling: Sampling,
+}
+
+/// How a builder picks the next task.
+#[derive(Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum Sampling {
+    /// Every task the mode can build is equally likely.
+    #[default]
+    Uniform,
+    /// Facts the child gets wrong or answers slowly come up more often.
+    Adaptive,
+    /// Facts are dealt from a shuffled deck, so each one comes up once before
+    /// any repeats and never twice in a row. With `commutative` set 7 x 8 and
+    /// 8 x 7 are one card, dealt in either order.
+    Deck {
+        #[serde(default)]
+        commutative: bool,
+    },
+}
+
+// The number of tasks an adaptive builder draws to pick the next one from.
+const ADAPTIVE_CANDIDATES: usize = 8;
+
+// Spreads the task indices over the seed space, so neighbouring tasks get
+// unrelated random streams.
+const SEED_STEP: u64 = 0x9E37_79B9_7F4A_7C15;
+
+// Larger decks are not dealt, their tasks are drawn uniformly instead.
+const MAX_DECK: usize = 10_000;
+
+// A card of a deck: the operands and the operation of a task.
+type Card = (i32, i32, Operation);
+
+/// A builder whose settings passed [`TaskBuilder::validate`]. The settings
+/// are public and saved with the assignments, so they are checked again
+/// before any task is built from them.
+pub struct CheckedBuilder<'a>(&'a TaskBuilder);
+
+impl<'a> CheckedBuilder<'a> {
+    pub(super) fn new(builder: &'a TaskBuilder) -> Self {
+        Self(builder)
+    }
+
+    /// Builds the task at `index` of an assignment. The same seed and index
+    /// always give the same task, so a set of tasks can be reproduced. The
+    /// task's clock is left for the view to start once it is shown.
+    pub fn build(&self, seed: u64, index: usize, history: &History) -> Task {
+        self.0.build(seed, index, history)
+    }
 }
 
 impl TaskBuilder {
-    pub fn build(&self) -> Task {
+    /// A builder of a mode that draws no operands of its own, such as a
+    /// review or a mix, with uniform sampling.
+    pub fn without_operands(mode: TaskBuilderMode) -> Self {
+        Self {
+            mode,
+            xrange: OperandRange::new(0, 0),
+            yrange: OperandRange::new(0, 0),
+            carry: Carry::Any,
+            unknowns: None,
+            xvalues: None,
+            yvalues: None,
+            sampling: Sampling::Uniform,
+        }
+    }
+
+    fn build(&self, seed: u64, index: usize, history: &History) -> Task {
+        let mut rng = StdRng::seed_from_u64(seed ^ (index as u64).wrapping_mul(SEED_STEP));
+        let rng = &mut rng;
+        match self.sampling {
+            Sampling::Uniform => self.new_task(rng, None),
+            Sampling::Adaptive => {
+                // Weak facts are likelier to be picked among the candidates,
+                // while facts the child knows still come up now and then.
+                let mut candidates: Vec<Task> = (0..ADAPTIVE_CANDIDATES)
+                    .map(|_| self.new_task(rng, None))
+                    .collect();
+                let weights = candidates
+                    .iter()
+                    .map(|t| Fact::of(t).map_or(1.0, |fact| history.weakness(&fact)));
+                let index = WeightedIndex::new(weights).map_or(0, |index| rng.sample(index));
+                candidates.swap_remove(index)
+            }
+            Sampling::Deck { commutative } => {
+                let commutative = commutative && self.commutes();
+                let Some(deck) = self.deck(commutative) else {
+                    return self.new_task(rng, None);
+                };
+                let (x, y, op) = deal(&deck, seed, index);
+                let swap = commutative
+                    && op != Operation::Subtraction
+                    && self.allows(y, x)
+                    && rng.gen_bool(0.5);
+                self.new_task(rng, Some(if swap { (y, x, op) } else { (x, y, op) }))
+            }
+        }
+    }
+
+    /// Whether the builder has a small set of facts to deal from a deck.
+    pub fn can_deal(&self) -> bool {
+        self.deck_size()
+            .map_or(false, |size| size > 0 && size <= MAX_DECK)
+    }
+
+    /// Whether swapping the operands of a task keeps it the same fact: the
+    /// factors of a product, the quotient and divisor of a division, the
+    /// terms of a sum.
+    pub fn commutes(&self) -> bool {
+        matches!(
+            self.mode,
+            TaskBuilderMode::Multiplication
+                | TaskBuilderMode::Division
+                | TaskBuilderMode::DivisionWithRemainder
+                | TaskBuilderMode::AdditionAndSubtraction
+        )
+    }
+
+    // The most cards the deck can have, counted without listing them, as
+    // the ranges can be huge. `None` if the mode has no facts to deal.
+    fn deck_size(&self) -> Option<usize> {
+        let [(xrange, xvalues), (yrange, yvalues)] = self.deck_operands();
+        let pairs =
+            || operand_count(&xrange, xvalues).saturating_mul(operand_count(&yrange, yvalues));
         match self.mode {
-            TaskBuilderMode::Multiplication => self.new_multiplication_task(),
-            TaskBuilderMode::AdditionAndSubtraction => self.new_addsub_task(),
+            TaskBuilderMode::Review { ref facts } => Some(facts.len()),
+            TaskBuilderMode::Multiplication
+            | TaskBuilderMode::Division
+            | TaskBuilderMode::DivisionWithRemainder => Some(pairs()),
+            // Each pair is an addition and a subtraction.
+            TaskBuilderMode::AdditionAndSubtraction => Some(pairs().saturating_mul(2)),
+            TaskBuilderMode::Complements { .. } => Some(operand_count(&xrange, xvalues)),
+            _ => None,
         }
     }
 
-    fn new_multiplication_task(&self) -> Task {
-        let mut rng = rand::thread_rng();
+    // The values the operands of a deck are taken from. With a carry
+    // constraint additions and subtractions are built from whole digit
+    // ranges rather than from the ranges themselves.
+    fn deck_operands(&self) -> [(OperandRange, Option<&[i32]>); 2] {
+        if self.mode == TaskBuilderMode::AdditionAndSubtraction && self.carry != Carry::Any {
+            return [
+                (OperandRange::digits(self.xrange.digit_count()), None),
+                (OperandRange::digits(self.yrange.digit_count()), None),
+            ];
+        }
+        [
+            (self.xrange, self.xvalues.as_deref()),
+            (self.yrange, self.yvalues.as_deref()),
+        ]
+    }
+
+    /// The cards a deck is made of, or `None` if the mode has no small set
+    /// of facts to deal. Commutative pairs are kept once, with the smaller
+    /// operand first.
+    fn deck(&self, commutative: bool) -> Option<Vec<Card>> {
+        if !self.can_deal() {
+            return None;
+        }
+        let op = match self.mode {
+            TaskBuilderMode::Review { ref facts } => {
+                return Some(facts.iter().map(|f| (f.x, f.y, f.op)).collect());
+            }
+            TaskBuilderMode::Multiplication => Operation::Multiplication,
+            TaskBuilderMode::Division => Operation::Division,
+            TaskBuilderMode::DivisionWithRemainder => Operation::DivisionWithRemainder,
+            _ => Operation::Addition,
+        };
+        let [(xrange, xvalues), (yrange, yvalues)] = self.deck_operands();
+        let xs = operand_values(&xrange, xvalues);
+        let ys = match self.mode {
+            TaskBuilderMode::Complements { .. } => vec![0],
+            _ => operand_values(&yrange, yvalues),
+        };
+        let mut deck = Vec::new();
+        for &x in &xs {
+            for &y in &ys {
+                if self.mode != TaskBuilderMode::AdditionAndSubtraction {
+                    deck.push((x, y, op));
+                    continue;
+                }
+                if self.carry.allows(operand::carries(x, y)) {
+                    deck.push((x, y, Operation::Addition));
+                }
+                // The larger operand is the minuend. A pair the other way
+                // round comes up on its own if the builder can draw it.
+                let (minuend, subtrahend) = (x.max(y), x.min(y));
+                if (x >= y || !self.allows(y, x))
+                    && self.carry.allows(operand::borrows(minuend, subtrahend))
+                {
+                    deck.push((minuend, subtrahend, Operation::Subtraction));
+                }
+            }
+        }
+        deck.retain(|&(x, y, op)| {
+            !(commutative && op != Operation::Subtraction && x > y && self.allows(y, x))
+        });
+        Some(deck)
+    }
+
+    // Whether a deck could have dealt these operands.
+    fn allows(&self, x: i32, y: i32) -> bool {
+        let [(xrange, xvalues), (yrange, yvalues)] = self.deck_operands();
+        has_operand(&xrange, xvalues, x) && has_operand(&yrange, yvalues, y)
+    }
+
+    // Builds a task of the builder's mode. Modes dealt from a deck use the
+    // dealt card instead of drawing their own operands.
+    fn new_task(&self, rng: &mut StdRng, dealt: Option<Card>) -> Task {
+        let mut operands = || {
+            dealt.map_or_else(
+                || (self.sample_x(rng), self.sample_y(rng)),
+                |(x, y, _)| (x, y),
+            )
+        };
+        let mut task = match self.mode {
+            TaskBuilderMode::Multiplication => self.new_multiplication_task(operands()),
+            TaskBuilderMode::AdditionAndSubtraction => match dealt {
+                Some((x, y, op)) => Task::new(x, y, op),
+                None => self.new_addsub_task(rng),
+            },
+            TaskBuilderMode::Division => self.new_division_task(operands()),
+            TaskBuilderMode::DivisionWithRemainder => {
+                let operands = operands();
+                self.new_remainder_task(rng, operands)
+            }
+            TaskBuilderMode::Chain { terms, negative } => self.new_chain_task(rng, terms, negative),
+            TaskBuilderMode::FlashAnzan { count, .. } => self.new_flash_task(rng, count),
+            TaskBuilderMode::SorobanReading => self.new_reading_task(rng),
+            TaskBuilderMode::Complements { target } => {
+                let x = dealt.map_or_else(|| self.sample_x(rng), |(x, _, _)| x);
+                self.new_complement_task(x, target)
+            }
+            TaskBuilderMode::Review { ref facts } => {
+                let dealt = dealt.map(|(x, y, op)| Fact::new(op, x, y));
+                self.new_review_task(rng, facts, dealt)
+            }
+            // The part builds the task, unknowns and all, as it would on its own.
+            TaskBuilderMode::Mix { ref parts } => {
+                match WeightedIndex::new(parts.iter().map(|p| p.weight)) {
+                    Ok(index) => return parts[rng.sample(index)].builder.new_task(rng, None),
+                    // Only a mix without any weight, which is never valid.
+                    Err(_) => Task::new(0, 0, Operation::Addition),
+                }
+            }
+        };
+        // Only plain two-operand expressions can ask for an operand or the operator.
+        if task.prompt == Prompt::Expression
+            && task.chain.is_empty()
+            && task.op != Operation::DivisionWithRemainder
+        {
+            task.unknown = self.unknowns().sample(rng);
+        }
+        task
+    }
+
+    fn sample_x<R: Rng + ?Sized>(&self, rng: &mut R) -> i32 {
+        sample_operand(rng, &self.xrange, &self.xvalues)
+    }
+
+    fn sample_y<R: Rng + ?Sized>(&self, rng: &mut R) -> i32 {
+        sample_operand(rng, &self.yrange, &self.yvalues)
+    }
+
+    pub fn unknowns(&self) -> UnknownWeights {
+        let only = |result, x, y| UnknownWeights {
+            result,
+            x,
+            y,
+            operator: 0,
+        };
+        self.unknowns.unwrap_or(match self.mode {
+            TaskBuilderMode::Complements { .. } => only(0, 1, 1),
+            _ => only(1, 0, 0),
+        })
+    }
+
+    fn new_multiplication_task(&self, (x, y): (i32, i32)) -> Task {
+        Task::new(x, y, Operation::Multiplication)
+    }
+
+    // The dividend is built as the product of the quotient and the divisor,
+    // so the division never leaves a remainder.
+    fn new_division_task(&self, (quotient, divisor): (i32, i32)) -> Task {
+        Task::new(quotient * divisor, divisor, Operation::Division)
+    }
+
+    fn new_remainder_task(&self, rng: &mut StdRng, (quotient, divisor): (i32, i32)) -> Task {
+        let remainder = rng.gen_range(0..divisor);
+        Task::new(
+            quotient * divisor + remainder,
+            divisor,
+            Operation::DivisionWithRemainder,
+        )
+    }
+
+    // The first term is drawn from `xrange` and the rest from `yrange`. Unless
+    // `negative` is set, a subtraction that would take the running total below
+    // zero is turned into an addition.
+    fn new_chain_task(&self, rng: &mut StdRng, terms: u32, negative: bool) -> Task {
+        let x = self.sample_x(rng);
+        let mut total = x;
+        let mut chain: Vec<Term> = (1..terms.max(2))
+            .map(|_| {
+                let value = self.sample_y(rng);
+                let op = if rng.gen_bool(0.5) && (negative || total >= value) {
+                    Operation::Subtraction
+                } else {
+                    Operation::Addition
+                };
+                total = op.apply(total, value);
+                Term { op, value }
+            })
+            .collect();
+        let first = chain.remove(0);
         Task {
-            x: rng.sample(self.xrange),
-            y: rng.sample(self.yrange),
-            op: Operation::Multiplication,
-            answer: None,
-            t_start: Some(Utc::now().timestamp_millis()),
-            t_finish: None,
+            chain,
+            ..Task::new(x, first.value, first.op)
+        }
+    }
+
+    fn new_flash_task(&self, rng: &mut StdRng, count: u32) -> Task {
+        let x = self.sample_x(rng);
+        let mut chain: Vec<Term> = (1..count.max(2))
+            .map(|_| Term {
+                op: Operation::Addition,
+                value: self.sample_x(rng),
+            })
+            .collect();
+        let first = chain.remove(0);
+        Task {
+            chain,
+            ..Task::new(x, first.value, first.op)
+        }
+    }
+
+    fn new_reading_task(&self, rng: &mut StdRng) -> Task {
+        Task {
+            prompt: Prompt::Soroban,
+            ..Task::new(self.sample_x(rng), 0, Operation::Addition)
         }
     }
 
-    fn new_addsub_task(&self) -> Task {
-        let mut rng = rand::thread_rng();
-        let op = if rng.gen_bool(0.5) {
+    fn new_complement_task(&self, x: i32, target: i32) -> Task {
+        Task::new(x, target - x, Operation::Addition)
+    }
+
+    fn new_review_task(&self, rng: &mut StdRng, facts: &[Fact], dealt: Option<Fact>) -> Task {
+        let fact = dealt
+            .or_else(|| facts.choose(rng).copied())
+            .unwrap_or(Fact::new(Operation::Addition, 0, 0));
+        // Commutative facts are stored in one order, ask them in both.
+        let commutative = matches!(fact.op, Operation::Addition | Operation::Multiplication);
+        let (x, y) = if commutative && rng.gen_bool(0.5) {
+            (fact.y, fact.x)
+        } else {
+            (fact.x, fact.y)
+        };
+        Task::new(x, y, fact.op)
+    }
+
+    fn new_addsub_task(&self, rng: &mut StdRng) -> Task {
+        let xdigits = self.xrange.digit_count();
+        let ydigits = self.yrange.digit_count();
+        // Borrowing needs a multi-digit minuend, so single digits only get
+        // additions when a carry is required.
+        let can_subtract = self.carry != Carry::Required
+            || operand::can_borrow(xdigits.max(ydigits), ydigits.min(xdigits));
+        let op = if !can_subtract || rng.gen_bool(0.5) {
             Operation::Addition
         } else {
             Operation::Subtraction
         };
 
-        let x = rng.sample(self.xrange);
-        let y = rng.sample(self.yrange);
-        let (x, y) = if op == Operation::Subtraction && x < y {
-            (y, x)
-        } else {
-            (x, y)
+        let (x, y) = match (self.carry, op) {
+            (Carry::Any, _) => {
+                let x = self.sample_x(rng);
+                let y = self.sample_y(rng);
+                if op == Operation::Subtraction && x < y {
+                    (y, x)
+                } else {
+                    (x, y)
+                }
+            }
+            (carry, Operation::Subtraction) => operand::subtraction_operands(
+                rng,
+                xdigits.max(ydigits),
+                xdigits.min(ydigits),
+                carry,
+            ),
+            (carry, _) => operand::addition_operands(rng, xdigits, ydigits, carry),
         };
-        Task {
-            x,
-            y,
-            op,
-            answer: None,
-            t_start: Some(Utc::now().timestamp_millis()),
-            t_finish: None,
-        }
+        Task::new(x, y, op)
+    }
+}
+
+fn sample_operand<R: Rng + ?Sized>(
+    rng: &mut R,
+    range: &OperandRange,
+    values: &Option<Vec<i32>>,
+) -> i32 {
+    match values.as_ref().and_then(|values| values.choose(rng)) {
+        Some(value) => *value,
+        None => range.sample(rng),
     }
 }
 
 pub enum TaskState {
     Correct,
+    /// Answered correctly after the given number of attempts.
+    CorrectAfter(usize),
     Wrong,
     Skipped,
+    TimedOut,
 }
 
 impl TaskState {
@@ -140,12 +844,170 @@ impl TaskState {
             TaskState::Correct => {
                 html! {<i class="w3-bar-item w3-round fa fa-solid fa-circle-check w3-teal"></i>}
             }
+            TaskState::CorrectAfter(_) => {
+                html! {<i class="w3-bar-item w3-round fa fa-solid fa-circle-check w3-amber"></i>}
+            }
             TaskState::Wrong => {
                 html! {<i class="w3-bar-item w3-round fa fa-solid fa-circle-xmark w3-red"></i>}
             }
             TaskState::Skipped => {
                 html! {<i class="w3-bar-item w3-round fa fa-solid fa-share w3-gray"></i>}
             }
+            TaskState::TimedOut => {
+                html! {<i class="w3-bar-item w3-round fa fa-solid fa-hourglass-end w3-orange"></i>}
+            }
         }
     }
 }
+
+// All values an operand can take, in ascending order.
+fn operand_values(range: &OperandRange, values: Option<&[i32]>) -> Vec<i32> {
+    let mut values = match values {
+        Some(values) => values.to_vec(),
+        None => (range.min..=range.max).collect(),
+    };
+    values.sort_unstable();
+    values.dedup();
+    values
+}
+
+// How many values an operand can take, counting repeated values once per
+// occurrence.
+fn operand_count(range: &OperandRange, values: Option<&[i32]>) -> usize {
+    match values {
+        Some(values) => values.len(),
+        None => (range.max as i64 - range.min as i64 + 1).max(0) as usize,
+    }
+}
+
+fn has_operand(range: &OperandRange, values: Option<&[i32]>, value: i32) -> bool {
+    match values {
+        Some(values) => values.contains(&value),
+        None => (range.min..=range.max).contains(&value),
+    }
+}
+
+// Deals the card at `index` from a deck that is reshuffled after each round.
+// A round never starts with the card the previous one ended with.
+fn deal<T: Copy + PartialEq>(deck: &[T], seed: u64, index: usize) -> T {
+    let mut cards = deck.to_vec();
+    let mut last = None;
+    for round in 0..=index / deck.len() {
+        cards = deck.to_vec();
+        let mut rng =
+            StdRng::seed_from_u64(seed.rotate_left(32) ^ (round as u64).wrapping_mul(SEED_STEP));
+        cards.shuffle(&mut rng);
+        if cards.len() > 1 && last == Some(cards[0]) {
+            cards.swap(0, 1);
+        }
+        last = cards.last().copied();
+    }
+    cards[index % deck.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn multiplication(sampling: Sampling) -> TaskBuilder {
+        TaskBuilder {
+            mode: TaskBuilderMode::Multiplication,
+            xrange: OperandRange::new(2, 9),
+            yrange: OperandRange::new(2, 9),
+            carry: Carry::Any,
+            unknowns: None,
+            xvalues: None,
+            yvalues: None,
+            sampling,
+        }
+    }
+
+    #[test]
+    fn same_seed_and_index_give_same_task() {
+        for sampling in [
+            Sampling::Uniform,
+            Sampling::Adaptive,
+            Sampling::Deck { commutative: true },
+        ] {
+            let builder = multiplication(sampling);
+            let builder = builder.check().unwrap();
+            for index in 0..50 {
+                let a = builder.build(42, index, &History::default());
+                let b = builder.build(42, index, &History::default());
+                assert!(a == b, "task {} differs: {} and {}", index, a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn deal_shows_every_card_once_per_round() {
+        let deck: Vec<(i32, i32)> = (0..7).map(|i| (i, 0)).collect();
+        let mut previous = None;
+        for round in 0..20 {
+            let mut cards: Vec<(i32, i32)> = (0..deck.len())
+                .map(|i| deal(&deck, 7, round * deck.len() + i))
+                .collect();
+            for card in &cards {
+                assert!(previous != Some(*card), "{:?} dealt twice in a row", card);
+                previous = Some(*card);
+            }
+            cards.sort_unstable();
+            assert_eq!(cards, deck);
+        }
+    }
+
+    #[test]
+    fn addition_deck_meets_the_carry_constraint() {
+        let builder = TaskBuilder {
+            mode: TaskBuilderMode::AdditionAndSubtraction,
+            carry: Carry::Forbidden,
+            ..multiplication(Sampling::Deck { commutative: true })
+        };
+        let deck = builder.deck(true).unwrap();
+        assert!(deck.iter().any(|c| c.2 == Operation::Subtraction));
+        let builder = builder.check().unwrap();
+        let mut tasks: Vec<(i32, i32, Operation)> = (0..deck.len())
+            .map(|index| {
+                let task = builder.build(3, index, &History::default());
+                match task.op {
+                    Operation::Addition => assert!(!operand::carries(task.x, task.y)),
+                    _ => assert!(task.x >= task.y && !operand::borrows(task.x, task.y)),
+                }
+                let (x, y) = match task.op {
+                    Operation::Addition => (task.x.min(task.y), task.x.max(task.y)),
+                    _ => (task.x, task.y),
+                };
+                (x, y, task.op)
+            })
+            .collect();
+        tasks.sort_unstable_by_key(|&(x, y, op)| (x, y, op == Operation::Addition));
+        tasks.dedup();
+        assert_eq!(tasks.len(), deck.len());
+    }
+
+    #[test]
+    fn legacy_answer_becomes_an_attempt() {
+        let json = r#"{"x": 6, "y": 7, "op": "Multiplication", "answer": 42,
+            "t_start": 1000, "t_finish": 3000}"#;
+        let task: Task = serde_json::from_str(json).unwrap();
+        assert_eq!(task.attempts.len(), 1);
+        assert!(
+            task.attempts[0]
+                == Attempt {
+                    answer: Answer::Number(42),
+                    time: 3000
+                }
+        );
+        assert!(task.correct());
+        assert!(!task.timed_out);
+        assert_eq!(task.section, 0);
+    }
+
+    #[test]
+    fn unanswered_legacy_task_has_no_attempts() {
+        let json = r#"{"x": 6, "y": 7, "op": "Multiplication", "answer": null,
+            "t_start": null, "t_finish": null}"#;
+        let task: Task = serde_json::from_str(json).unwrap();
+        assert!(task.attempts.is_empty());
+    }
+}
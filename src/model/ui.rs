@@ -5,7 +5,17 @@ use uuid::Uuid;
 
 use yewdux::{prelude::*, storage};
 
-use super::{assignment::Assignment, task::Task};
+use super::{
+    assignment::Assignment,
+    config::ConfigError,
+    criteria::Criteria,
+    fact::History,
+    memory::Memory,
+    operand::Carry,
+    preset::Preset,
+    schedule::{Schedule, Template},
+    task::{Answer, Sampling, Task},
+};
 
 #[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Homework {
@@ -36,8 +46,8 @@ impl From<Vec<Assignment>> for Homework {
             .iter()
             .filter(|a| !a.is_done() && a.due_date.is_some())
             .for_each(|a| {
-                let due_date = a.due_date.unwrap().clone();
-                let id = a.id.clone();
+                let due_date = a.due_date.unwrap();
+                let id = a.id;
                 map.entry(due_date).or_default().push(id);
             });
         Self { homework: map }
@@ -47,6 +57,12 @@ impl From<Vec<Assignment>> for Homework {
 #[derive(Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Assignments {
     pub assignments: Vec<Assignment>,
+    /// Spaced repetition state of the facts practiced in all assignments.
+    #[serde(default)]
+    pub memory: Memory,
+    /// When homework is given and what it is.
+    #[serde(default)]
+    pub schedule: Schedule,
 }
 
 impl Store for Assignments {
@@ -88,30 +104,256 @@ impl Assignments {
     pub fn submit_task(&mut self, id: Uuid, task: Task) {
         let assignment = self.assignments.iter_mut().find(|a| a.id == id);
         if let Some(assignment) = assignment {
+            if !assignment.is_done() {
+                self.memory.record(&task, Utc::now().naive_utc().date());
+            }
             assignment.submit_task(task);
         }
     }
 
-    pub fn fill(&mut self) {
+    /// Makes sure the assignment has a pending task and starts its clock.
+    /// Adaptive builders get the history of all assignments, so that they
+    /// can focus on the weak facts.
+    pub fn show_task(&mut self, id: Uuid) {
+        let Some(assignment) = self.get(id) else {
+            return;
+        };
+        let history = match assignment.builder.sampling {
+            Sampling::Adaptive => History::new(&self.assignments),
+            Sampling::Uniform | Sampling::Deck { .. } => History::default(),
+        };
+        if let Some(assignment) = self.assignments.iter_mut().find(|a| a.id == id) {
+            assignment.show_task(&history);
+        }
+    }
+
+    /// Submits the pending task as timed out once its time limit has run out.
+    pub fn time_out(&mut self, id: Uuid) {
+        let now = Utc::now().timestamp_millis();
+        let task = self
+            .assignments
+            .iter_mut()
+            .find(|a| a.id == id)
+            .and_then(|a| a.take_timed_out(now));
+        if let Some(task) = task {
+            self.submit_task(id, task);
+        }
+    }
+
+    /// Answers the pending task, which is submitted unless it is retried.
+    pub fn answer_task(&mut self, id: Uuid, answer: Answer) {
+        let now = Utc::now().timestamp_millis();
+        let task = self
+            .assignments
+            .iter_mut()
+            .find(|a| a.id == id)
+            .and_then(|a| a.take_answered(answer, now));
+        if let Some(task) = task {
+            self.submit_task(id, task);
+        }
+    }
+
+    pub fn set_retry(&mut self, id: Uuid, retry: bool) {
+        if let Some(assignment) = self.assignments.iter_mut().find(|a| a.id == id) {
+            assignment.retry = retry;
+        }
+    }
+
+    /// Submits the pending task unanswered, if the child may skip it.
+    pub fn skip(&mut self, id: Uuid) {
+        let now = Utc::now().timestamp_millis();
+        let task = self
+            .assignments
+            .iter_mut()
+            .find(|a| a.id == id)
+            .and_then(|a| a.take_skipped(now));
+        if let Some(task) = task {
+            self.submit_task(id, task);
+        }
+    }
+
+    pub fn set_skips(&mut self, id: Uuid, max_skips: Option<u32>, requeue_skipped: bool) {
+        if let Some(assignment) = self.assignments.iter_mut().find(|a| a.id == id) {
+            assignment.max_skips = max_skips;
+            assignment.requeue_skipped = requeue_skipped;
+        }
+    }
+
+    /// Sets the criteria of an assignment that has no tasks yet.
+    pub fn set_criteria(&mut self, id: Uuid, criteria: Criteria) {
+        if let Some(assignment) = self
+            .assignments
+            .iter_mut()
+            .find(|a| a.id == id && a.tasks.is_empty())
+        {
+            assignment.criteria = criteria;
+        }
+    }
+
+    pub fn set_task_limit(&mut self, id: Uuid, task_limit: Option<u32>) {
+        if let Some(assignment) = self.assignments.iter_mut().find(|a| a.id == id) {
+            assignment.task_limit = task_limit;
+        }
+    }
+
+    pub fn expire(&mut self, id: Uuid) {
+        if let Some(assignment) = self.assignments.iter_mut().find(|a| a.id == id) {
+            assignment.expire();
+        }
+    }
+
+    pub fn set_sampling(&mut self, id: Uuid, sampling: Sampling) {
+        if let Some(assignment) = self.assignments.iter_mut().find(|a| a.id == id) {
+            assignment.builder.sampling = sampling;
+            for section in assignment.sections.iter_mut() {
+                section.builder.sampling = sampling;
+            }
+        }
+    }
+
+    /// Sets the seed of an assignment that has no tasks yet, so that it
+    /// gives the same tasks as another assignment of its kind with that seed,
+    /// e.g. on another device.
+    pub fn set_seed(&mut self, id: Uuid, seed: u64) {
+        if let Some(assignment) = self
+            .assignments
+            .iter_mut()
+            .find(|a| a.id == id && a.tasks.is_empty())
+        {
+            assignment.seed = seed;
+            assignment.pending = None;
+        }
+    }
+
+    pub fn set_abacus(&mut self, id: Uuid, abacus: bool) {
+        if let Some(assignment) = self.assignments.iter_mut().find(|a| a.id == id) {
+            assignment.abacus = abacus;
+        }
+    }
+
+    pub fn set_schedule(&mut self, schedule: Schedule) {
+        self.schedule = schedule;
+    }
+
+    /// Gives the homework of the days since the latest pending homework up
+    /// to today, as the schedule says. Days that already have homework are
+    /// left alone. Presets are looked up in `presets`.
+    pub fn fill(&mut self, presets: &Presets) {
         let today: NaiveDate = Utc::now().naive_utc().date();
         let latest = self
             .assignments
             .iter()
-            .filter(|a| !a.is_done() && a.due_date.is_some())
-            .max_by(|a1, a2| a1.due_date.cmp(&a2.due_date));
-
-        if let Some(assignment) = latest {
-            let mut next = assignment
-                .due_date
-                .unwrap()
-                .checked_add_days(Days::new(1))
-                .unwrap();
-            while next <= today {
-                self.push(Assignment::new_sd_sd_multiplication(100, Some(next)));
-                next = next.checked_add_days(Days::new(1)).unwrap();
+            .filter(|a| !a.is_done())
+            .filter_map(|a| a.due_date)
+            .max();
+        let first = match latest {
+            Some(day) => day.checked_add_days(Days::new(1)).unwrap(),
+            None => today,
+        };
+        let mut homework = Vec::new();
+        for day in self.schedule.due_days(first, today) {
+            if self.assignments.iter().any(|a| a.due_date == Some(day)) {
+                continue;
+            }
+            for template in self.schedule.templates(day) {
+                match self.homework(day, template, presets) {
+                    Ok(Some(assignment)) => homework.push(assignment),
+                    Ok(None) => {}
+                    Err(err) => {
+                        log::error!("failed to create {} for {}: {}", template.name(), day, err)
+                    }
+                }
+            }
+        }
+        for assignment in homework {
+            self.push(assignment);
+        }
+    }
+
+    // The review homework goes over the facts that are due by the day, or
+    // practices multiplication while nothing is due yet. A preset that no
+    // longer exists gives no homework.
+    fn homework(
+        &self,
+        day: NaiveDate,
+        template: &Template,
+        presets: &Presets,
+    ) -> Result<Option<Assignment>, ConfigError> {
+        let due_date = Some(day);
+        let assignment = match template {
+            Template::Review => {
+                let due = self.memory.due(day);
+                if due.is_empty() {
+                    Assignment::new_sd_sd_multiplication(100, due_date)
+                } else {
+                    let num_tasks = (due.len() as i32 * 2).clamp(20, 100);
+                    Assignment::new_review(due, num_tasks, due_date)
+                }
+            }
+            Template::Multiplication => Assignment::new_sd_sd_multiplication(100, due_date),
+            Template::Division => Assignment::new_sd_sd_division(100, due_date),
+            Template::Addition => Assignment::new_addition(1, 1, Carry::Any, 100, due_date),
+            Template::Sprint => Assignment::new_sprint(60, due_date),
+            Template::Preset(name) => match presets.get(name) {
+                Some(preset) => preset.design.assignment(name.clone(), due_date),
+                None => {
+                    log::warn!("no preset {} for the homework of {}", name, day);
+                    return Ok(None);
+                }
+            },
+        };
+        assignment.map(Some)
+    }
+}
+
+/// The assignment designs saved by the user, listed next to the built-in games.
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Presets {
+    pub presets: Vec<Preset>,
+}
+
+impl Store for Presets {
+    fn new() -> Self {
+        init_listener(PresetsListener);
+        match storage::load::<Presets>(storage::Area::Local) {
+            Ok(presets) => presets.unwrap_or_default(),
+            Err(err) => {
+                log::error!("failed to load Presets {}", err);
+                Default::default()
             }
-        } else {
-            self.push(Assignment::new_sd_sd_multiplication(100, Some(today)));
+        }
+    }
+
+    fn should_notify(&self, old: &Self) -> bool {
+        self != old
+    }
+}
+
+impl Presets {
+    /// Saves the preset, replacing the one with the same name.
+    pub fn push(&mut self, preset: Preset) {
+        match self.presets.iter_mut().find(|p| p.name == preset.name) {
+            Some(existing) => *existing = preset,
+            None => self.presets.push(preset),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Preset> {
+        self.presets.iter().find(|p| p.name == name)
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.presets.retain(|p| p.name != name);
+    }
+}
+
+struct PresetsListener;
+impl Listener for PresetsListener {
+    type Store = Presets;
+
+    fn on_change(&mut self, state: std::rc::Rc<Self::Store>) {
+        if let Err(err) = storage::save(state.as_ref(), storage::Area::Local) {
+            log::error!("failed to save Presets to local storage: {}", err);
         }
     }
 }